@@ -0,0 +1,49 @@
+//! Measures the cost of the reference counted, thread-safe storage of `Matrix`.
+//!
+//! Run with `cargo bench --bench storage`.
+
+use std::{hint::black_box, rc::Rc, sync::Arc, time::{Duration, Instant}};
+
+use neural_network::*;
+
+fn bench<F: FnMut()>(name: &str, iterations: u32, mut f: F) -> Duration {
+    // warm up
+    for _ in 0..iterations / 10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iter = start.elapsed() / iterations;
+    println!("{:<40} {:>12?} / iter", name, per_iter);
+    per_iter
+}
+
+fn main() {
+    let data = vec![0.5_f32; 64];
+
+    // reference counting is the only part of the storage that changed from Rc to Arc
+    let rc = Rc::new(data.clone());
+    let rc_time = bench("Rc::clone + drop", 10_000_000, || {
+        black_box(rc.clone());
+    });
+    let arc = Arc::new(data.clone());
+    let arc_time = bench("Arc::clone + drop", 10_000_000, || {
+        black_box(arc.clone());
+    });
+    println!("{:<40} {:>12.2}x", "Arc / Rc", arc_time.as_secs_f64() / rc_time.as_secs_f64());
+
+    let mat = Matrix::from_vec(data, (8, 8), true);
+    bench("Matrix::clone + drop", 10_000_000, || {
+        black_box(mat.clone());
+    });
+
+    // a typical training step, dominated by arithmetic rather than reference counting
+    let w = Matrix::randn(-1., 1., (32, 32), true);
+    let x = Matrix::randn(-1., 1., (32, 16), false);
+    bench("forward + backward (32x32 @ 32x16)", 200, || {
+        let out = w.matmul(&x).unwrap().sigmoid().sum_all().unwrap();
+        black_box(out.backward().unwrap());
+    });
+}
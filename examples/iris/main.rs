@@ -10,7 +10,7 @@ const EPOCHS: usize = 300;
 const NN_CONFIG: [usize; 5] = [4, 2, 4, 5, 3];
 const LEARNING_RATE: f32 = 0.2;
 
-const SVG_PATH: &'static str = "examples/iris/iris_nn_loss.svg"; 
+const SVG_PATH: &str = "examples/iris/iris_nn_loss.svg"; 
 
 fn read_csv(file_name: &str, drop: Vec<usize>) -> Result<Vec<Vec<f32>>, Box<dyn Error>>{
     let content = std::fs::read_to_string(file_name)?;
@@ -26,11 +26,10 @@ fn read_csv(file_name: &str, drop: Vec<usize>) -> Result<Vec<Vec<f32>>, Box<dyn
         let record = result?;
 
         table.push((0..record.len())
-            .map(|x| drop
+            .flat_map(|x| drop
                 .iter()
                 .map(move |&y| (x, y))
             )
-            .flatten()
             .filter(|(i, j)| *i != *j)
             .map(|(x, _)| {
                 let x = record[x].trim();
@@ -53,7 +52,9 @@ fn read_csv(file_name: &str, drop: Vec<usize>) -> Result<Vec<Vec<f32>>, Box<dyn
     Ok(table)
 }
 
-fn partition_labels(table: Vec<Vec<f32>>, y_index: usize) -> Result<(Vec<Vec<f32>>, Vec<f32>), Box<dyn Error>> {
+type Dataset = (Vec<Vec<f32>>, Vec<f32>);
+
+fn partition_labels(table: Vec<Vec<f32>>, y_index: usize) -> Result<Dataset, Box<dyn Error>> {
 
     let csv_column_len = table[0].len() - 1;
     if y_index > csv_column_len {
@@ -84,8 +85,8 @@ fn main() -> Result<(), Box<dyn Error>>{
     
     // print 10 records
     println!("Records 45-55:");
-    for i in 45..55 {
-        println!("{}: {:?}", i, input_dataset[i]);
+    for (i, record) in input_dataset.iter().enumerate().take(55).skip(45) {
+        println!("{}: {:?}", i, record);
     }
 
     // partition columns where the labels are index 4
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use neural_network::{Activation, Conv2d, Dense, Flatten, MaxPool, Sequential, NN};
+use rand::Rng;
+
+const SIZE: usize = 8;
+const SAMPLES: usize = 200;
+const BATCH_SIZE: usize = 10;
+const EPOCHS: usize = 30;
+
+// an 8 x 8 image with a noisy horizontal (label 1) or vertical (label 0) bar at a random position
+fn image(rng: &mut impl Rng, horizontal: bool) -> Vec<f32> {
+    let position = rng.gen_range(0..SIZE);
+    (0..SIZE * SIZE)
+        .map(|i| {
+            let (y, x) = (i / SIZE, i % SIZE);
+            let on = if horizontal { y == position } else { x == position };
+            let noise = rng.gen_range(-0.2..0.2);
+            if on { 1. + noise } else { noise }
+        })
+        .collect()
+}
+
+fn dataset(rng: &mut impl Rng, samples: usize) -> (Vec<Vec<f32>>, Vec<f32>) {
+    (0..samples)
+        .map(|i| {
+            let horizontal = i % 2 == 0;
+            (image(rng, horizontal), if horizontal { 1. } else { 0. })
+        })
+        .unzip()
+}
+
+fn accuracy(nn: &NN, x: &[Vec<f32>], y: &[f32]) -> Result<f32, Box<dyn Error>> {
+    let classes = nn.predict_classes(x)?;
+    let correct = y
+        .iter()
+        .zip(classes)
+        .filter(|(&label, class)| label as usize == *class)
+        .count();
+    Ok(correct as f32 / y.len() as f32)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut rng = rand::thread_rng();
+    let (x_train, y_train) = dataset(&mut rng, SAMPLES);
+    let (x_test, y_test) = dataset(&mut rng, SAMPLES / 4);
+
+    // 1 x 8 x 8 -> conv 4 x 8 x 8 -> pool 4 x 4 x 4 -> 1
+    let conv = Conv2d::new(1, 4, (3, 3), (SIZE, SIZE)).with_padding((1, 1));
+    let (channels, height, width) = conv.output_shape();
+    let pool = MaxPool::new2d(channels, (height, width), (2, 2), (2, 2));
+    let (channels, height, width) = pool.output_shape();
+
+    let model = Sequential::new()
+        .push(conv)
+        .push(Activation::Relu)
+        .push(pool)
+        .push(Flatten::new())
+        .push(Dense::new(channels * height * width, 1, Activation::Sigmoid));
+
+    let mut nn = NN::from_model(model, 0.05);
+    print!("{}", nn.summary());
+
+    let losses = nn.train(&x_train, &y_train, BATCH_SIZE, EPOCHS)?;
+    println!("final loss: {:.4}", losses.last().unwrap());
+    println!("test accuracy: {:.2}", accuracy(&nn, &x_test, &y_test)?);
+
+    Ok(())
+}
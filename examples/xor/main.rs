@@ -7,7 +7,7 @@ use std::env;
 
 const BATCH_SIZE: usize = 1;
 const EPOCHS: usize = 1000;
-const SVG_PATH: &'static str = "examples/xor/xor_nn_loss.svg"; 
+const SVG_PATH: &str = "examples/xor/xor_nn_loss.svg"; 
 
 fn main() -> Result<(), Box<dyn Error>>{
     env::set_var("RUST_BACKTRACE", "1");
@@ -51,10 +51,10 @@ fn main() -> Result<(), Box<dyn Error>>{
     Page::single(&v).save(SVG_PATH).unwrap();
     
     // test the model against the inputs
-    println!("prediction for input (0, 0) = {}", nn.forward((&x_train[0]).into())?.get(0, 0));
-    println!("prediction for input (0, 1) = {}", nn.forward((&x_train[1]).into())?.get(0, 0));
-    println!("prediction for input (1, 0) = {}", nn.forward((&x_train[2]).into())?.get(0, 0));
-    println!("prediction for input (1, 1) = {}", nn.forward((&x_train[3]).into())?.get(0, 0));
+    let predictions = nn.predict(&x_train)?;
+    for (i, x) in x_train.iter().enumerate() {
+        println!("prediction for input ({}, {}) = {}", x[0], x[1], predictions.get(i, 0));
+    }
     
     Ok(())
 }
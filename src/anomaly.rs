@@ -0,0 +1,117 @@
+use std::{
+    cell::Cell,
+    sync::{Arc, atomic::{AtomicBool, Ordering}}
+};
+
+use crate::{Float, Matrix, error::MatrixError};
+
+static ANOMALY_DETECTION: AtomicBool = AtomicBool::new(false);
+
+// maximum number of ops reported in the parent chain of an anomaly
+const MAX_CHAIN_LEN: usize = 32;
+
+thread_local! {
+    // ops executed while computing gradients are checked by `backward` instead
+    static SUSPENDED: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pass {
+    Forward,
+    Backward
+}
+
+/// Enables or disables anomaly detection for all threads.
+///
+/// While enabled, the output of every `Matrix` operation and every gradient computed
+/// in `Matrix::backward` is checked for NaN or infinite values. The first non-finite
+/// value is reported as a `MatrixError::AnomalyError`. Operations that return a
+/// `Result` fail immediately, the others attach the error to their result, and it is
+/// reported by the next fallible operation or call to `backward` depending on that result.
+pub fn set_anomaly_detection(enabled: bool) {
+    ANOMALY_DETECTION.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_anomaly_detection_enabled() -> bool {
+    ANOMALY_DETECTION.load(Ordering::Relaxed)
+}
+
+fn is_active() -> bool {
+    is_anomaly_detection_enabled() && !SUSPENDED.with(|suspended| suspended.get())
+}
+
+// follows the first operand that is itself the result of an op
+fn op_chain<T: Float>(node: &Matrix<T>) -> Vec<String> {
+    let mut chain = vec![];
+    let mut current = Some(node);
+    while let Some(node) = current {
+        let Some(op) = node.op() else {
+            break;
+        };
+        if chain.len() == MAX_CHAIN_LEN {
+            chain.push("...".to_string());
+            break;
+        }
+        chain.push(format!("{}#{}", op, node.id()));
+        let operands = op.operands();
+        current = operands
+            .iter()
+            .find(|mat| mat.op().is_some())
+            .or(operands.first())
+            .copied();
+    }
+    chain
+}
+
+pub(crate) fn anomaly_error<T: Float>(node: &Matrix<T>, pass: Pass) -> MatrixError {
+    let (op, input_shapes) = match node.op() {
+        Some(op) => (op.to_string(), op.operands().iter().map(|mat| mat.shape()).collect()),
+        None => ("Leaf".to_string(), vec![])
+    };
+    MatrixError::AnomalyError {
+        op,
+        node_id: node.id(),
+        pass,
+        input_shapes,
+        output_shape: node.shape(),
+        op_chain: op_chain(node)
+    }
+}
+
+/// The anomaly carried by the result `node` of an op: the first one carried by its
+/// operands, otherwise its own if it holds a non-finite value.
+pub(crate) fn check_output<T: Float>(node: &Matrix<T>) -> Option<Arc<MatrixError>> {
+    if !is_active() {
+        return None;
+    }
+    let inherited = node.op()
+        .as_ref()
+        .and_then(|op| op.operands().into_iter().find_map(|mat| mat.anomaly().cloned()));
+    inherited.or_else(|| {
+        let finite = node.data().iter().all(|x| x.is_finite());
+        (!finite).then(|| Arc::new(anomaly_error(node, Pass::Forward)))
+    })
+}
+
+/// Returns the anomaly carried by `node`, if any, as an error.
+pub(crate) fn raise<T: Float>(node: &Matrix<T>) -> Result<(), MatrixError> {
+    match node.anomaly() {
+        Some(err) if is_anomaly_detection_enabled() => Err(err.as_ref().clone()),
+        _ => Ok(())
+    }
+}
+
+/// Disables the output checks of the current thread until the guard is dropped.
+pub(crate) struct SuspendGuard(bool);
+
+impl SuspendGuard {
+    pub(crate) fn new() -> Self {
+        Self(SUSPENDED.with(|suspended| suspended.replace(true)))
+    }
+}
+
+impl Drop for SuspendGuard {
+    fn drop(&mut self) {
+        SUSPENDED.with(|suspended| suspended.set(self.0));
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{Activation, Dense, LayerNorm, Matrix, Module, Parameter, error::MatrixError};
+
+// Sequences are stored like the inputs of the recurrent layers, the tokens of every sample
+// stacked along the rows: (len * d_model, samples). Internally, attention works on tokens as
+// columns, (d_model, samples * len), with the tokens of every sample next to each other.
+
+// added to the scores of masked positions; finite, such that anomaly detection stays quiet
+const MASKED: f32 = -1e9;
+
+// (len * d_model, samples) -> (d_model, samples * len), together with len
+fn tokens(xs: &Matrix, d_model: usize) -> Result<(Matrix, usize), MatrixError> {
+    let (rows, samples) = xs.shape();
+    if rows == 0 || rows % d_model != 0 {
+        return Err(MatrixError::ShapeMismatchError {
+            a_shape: (d_model, samples),
+            b_shape: xs.shape(),
+            op: "attention".to_string()
+        });
+    }
+    let len = rows / d_model;
+    // the tokens of sample n are column n of the (len, d_model) blocks
+    let parts = (0..samples)
+        .map(|n| xs.slice_cols(n, n + 1)?.reshape((len, d_model)).map(|mat| mat.t()))
+        .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+    Ok((Matrix::concat(&parts.iter().collect::<Vec<&Matrix>>(), 1)?, len))
+}
+
+// (d_model, samples * len) -> (len * d_model, samples)
+fn sequences(tokens: &Matrix, len: usize) -> Result<Matrix, MatrixError> {
+    let (d_model, cols) = tokens.shape();
+    let parts = (0..cols / len)
+        .map(|n| tokens.slice_cols(n * len, (n + 1) * len)?.t().reshape((len * d_model, 1)))
+        .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+    Matrix::concat(&parts.iter().collect::<Vec<&Matrix>>(), 1)
+}
+
+/// Attention of every query over the keys, for tokens as columns: a `query` of shape
+/// `(d_k, q_len)`, a `key` of shape `(d_k, k_len)` and a `value` of shape `(d_v, k_len)`
+/// give an output of shape `(d_v, q_len)`, where column `j` is the average of the values
+/// weighted by `softmax(key^T @ query / sqrt(d_k))` of query `j`.
+///
+/// Query `j` does not attend to key `i` where element `(i, j)` of the `(k_len, q_len)` mask is 0.
+pub fn scaled_dot_product_attention(query: &Matrix, key: &Matrix, value: &Matrix, mask: Option<&Matrix>) -> Result<Matrix, MatrixError> {
+    let d_k = query.shape().0;
+    let scores = key.t().matmul(query)?.mul_scalar(1. / (d_k as f32).sqrt());
+
+    let scores = match mask {
+        Some(mask) if mask.shape() != scores.shape() => {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: scores.shape(),
+                b_shape: mask.shape(),
+                op: "attention mask".to_string()
+            });
+        }
+        Some(mask) => scores.add(&mask.add_scalar(-1.).mul_scalar(-MASKED))?,
+        None => scores
+    };
+
+    value.matmul(&scores.softmax(0)?)
+}
+
+/// Mask letting every token of a sequence of `len` tokens attend only to itself and earlier tokens.
+pub fn causal_mask(len: usize) -> Matrix {
+    let data = (0..len * len)
+        .map(|k| if k / len <= k % len { 1. } else { 0. })
+        .collect();
+    Matrix::from_vec(data, (len, len), false)
+}
+
+/// Sinusoidal positional encodings of shape `(d_model, len)`, where column `pos` holds
+/// `sin(pos / 10000^(2i / d_model))` in row `2i` and the cosine in row `2i + 1`.
+pub fn positional_encoding(len: usize, d_model: usize) -> Matrix {
+    let mut data = Vec::with_capacity(d_model * len);
+    for row in 0..d_model {
+        let frequency = 1. / 10000_f32.powf((row - row % 2) as f32 / d_model as f32);
+        for pos in 0..len {
+            let angle = pos as f32 * frequency;
+            data.push(if row % 2 == 0 { angle.sin() } else { angle.cos() });
+        }
+    }
+    Matrix::from_vec(data, (d_model, len), false)
+}
+
+// a linear layer uniform in [-1/sqrt(inputs), 1/sqrt(inputs)], with parameters prefixed by name
+fn linear(inputs: usize, outputs: usize, activation: Activation, name: &str) -> Dense {
+    let bound = 1. / (inputs as f32).sqrt();
+    let mut dense = Dense::new(inputs, outputs, activation);
+    for param in dense.parameters_mut() {
+        let shape = param.value().shape();
+        param.set(Matrix::randn(-bound, bound, shape, true));
+        param.add_prefix(name);
+    }
+    dense
+}
+
+/// Multi-head self-attention over sequences of `d_model` features per token. The queries,
+/// keys and values are projected by `query`, `key` and `value`, split into `heads` heads of
+/// `d_model / heads` features, attended to separately and projected back by `output`.
+#[derive(Debug, Clone)]
+pub struct MultiHeadAttention {
+    query: Dense,
+    key: Dense,
+    value: Dense,
+    output: Dense,
+    heads: usize,
+    causal: bool
+}
+
+impl MultiHeadAttention {
+    /// Panics unless `d_model` is a multiple of `heads`.
+    pub fn new(d_model: usize, heads: usize) -> Self {
+        assert!(heads > 0 && d_model.is_multiple_of(heads), "d_model {} is not a multiple of {} heads", d_model, heads);
+        Self {
+            query: linear(d_model, d_model, Activation::None, "query"),
+            key: linear(d_model, d_model, Activation::None, "key"),
+            value: linear(d_model, d_model, Activation::None, "value"),
+            output: linear(d_model, d_model, Activation::None, "output"),
+            heads,
+            causal: false
+        }
+    }
+
+    /// Lets every token attend only to itself and earlier tokens.
+    pub fn with_causal_mask(mut self) -> Self {
+        self.causal = true;
+        self
+    }
+
+    pub fn d_model(&self) -> usize {
+        self.query.weight().value().shape().1
+    }
+
+    /// Self-attention over `tokens` of shape `(d_model, samples * len)`, the `len` tokens
+    /// of every sample next to each other as columns. Returns a matrix of the same shape.
+    pub fn attend(&self, tokens: &Matrix, len: usize) -> Result<Matrix, MatrixError> {
+        let (query, key, value) = (self.query.forward(tokens)?, self.key.forward(tokens)?, self.value.forward(tokens)?);
+        let mask = self.causal.then(|| causal_mask(len));
+        let d_head = self.d_model() / self.heads;
+
+        let samples = (0..tokens.shape().1 / len)
+            .map(|n| {
+                let cols = |mat: &Matrix| mat.slice_cols(n * len, (n + 1) * len);
+                let (query, key, value) = (cols(&query)?, cols(&key)?, cols(&value)?);
+                let heads = (0..self.heads)
+                    .map(|h| {
+                        let rows = |mat: &Matrix| mat.slice_rows(h * d_head, (h + 1) * d_head);
+                        scaled_dot_product_attention(&rows(&query)?, &rows(&key)?, &rows(&value)?, mask.as_ref())
+                    })
+                    .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+                Matrix::concat(&heads.iter().collect::<Vec<&Matrix>>(), 0)
+            })
+            .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+
+        self.output.forward(&Matrix::concat(&samples.iter().collect::<Vec<&Matrix>>(), 1)?)
+    }
+}
+
+impl Module for MultiHeadAttention {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let (tokens, len) = tokens(xs, self.d_model())?;
+        sequences(&self.attend(&tokens, len)?, len)
+    }
+
+    fn describe(&self) -> String {
+        format!("MultiHeadAttention(d_model = {}, heads = {}{})", self.d_model(), self.heads, if self.causal { ", causal" } else { "" })
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        [&self.query, &self.key, &self.value, &self.output]
+            .into_iter()
+            .flat_map(|dense| dense.parameters())
+            .collect()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        [&mut self.query, &mut self.key, &mut self.value, &mut self.output]
+            .into_iter()
+            .flat_map(|dense| dense.parameters_mut())
+            .collect()
+    }
+}
+
+/// Adds the sinusoidal `positional_encoding` to sequences of `d_model` features per token.
+#[derive(Debug, Clone)]
+pub struct PositionalEncoding {
+    d_model: usize
+}
+
+impl PositionalEncoding {
+    pub fn new(d_model: usize) -> Self {
+        Self { d_model }
+    }
+}
+
+impl Module for PositionalEncoding {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let (rows, _) = xs.shape();
+        if rows % self.d_model != 0 {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: (self.d_model, xs.shape().1),
+                b_shape: xs.shape(),
+                op: "positional encoding".to_string()
+            });
+        }
+        let len = rows / self.d_model;
+        let encoding = positional_encoding(len, self.d_model).t().reshape((rows, 1))?;
+        xs.add(&encoding)
+    }
+
+    fn describe(&self) -> String {
+        format!("PositionalEncoding({})", self.d_model)
+    }
+}
+
+/// Transformer encoder block (post-norm): self-attention and a feed-forward network of
+/// `d_ff` hidden units with ReLU, applied to every token, each followed by a residual
+/// connection and layer normalization:
+///
+/// `x = norm_1(x + attention(x))` and `x = norm_2(x + linear_2(relu(linear_1(x))))`.
+#[derive(Debug, Clone)]
+pub struct TransformerEncoderBlock {
+    attention: MultiHeadAttention,
+    norm_1: LayerNorm,
+    linear_1: Dense,
+    linear_2: Dense,
+    norm_2: LayerNorm
+}
+
+impl TransformerEncoderBlock {
+    pub fn new(d_model: usize, heads: usize, d_ff: usize) -> Self {
+        let mut attention = MultiHeadAttention::new(d_model, heads);
+        let (mut norm_1, mut norm_2) = (LayerNorm::new(d_model), LayerNorm::new(d_model));
+        for (module, name) in [(&mut attention as &mut dyn Module, "attention"), (&mut norm_1, "norm_1"), (&mut norm_2, "norm_2")] {
+            for param in module.parameters_mut() {
+                param.add_prefix(name);
+            }
+        }
+        Self {
+            attention,
+            norm_1,
+            linear_1: linear(d_model, d_ff, Activation::Relu, "linear_1"),
+            linear_2: linear(d_ff, d_model, Activation::None, "linear_2"),
+            norm_2
+        }
+    }
+
+    pub fn with_causal_mask(mut self) -> Self {
+        self.attention = self.attention.with_causal_mask();
+        self
+    }
+}
+
+impl Module for TransformerEncoderBlock {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let (xs, len) = tokens(xs, self.attention.d_model())?;
+        let xs = self.norm_1.forward(&xs.add(&self.attention.attend(&xs, len)?)?)?;
+        let hidden = self.linear_2.forward(&self.linear_1.forward(&xs)?)?;
+        let xs = self.norm_2.forward(&xs.add(&hidden)?)?;
+        sequences(&xs, len)
+    }
+
+    fn describe(&self) -> String {
+        format!("TransformerEncoderBlock(d_model = {}, d_ff = {})", self.attention.d_model(), self.linear_1.weight().value().shape().0)
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        self.children()
+            .into_iter()
+            .flat_map(|module| module.parameters())
+            .collect()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        let mut params = self.attention.parameters_mut();
+        params.extend(self.norm_1.parameters_mut());
+        params.extend(self.linear_1.parameters_mut());
+        params.extend(self.linear_2.parameters_mut());
+        params.extend(self.norm_2.parameters_mut());
+        params
+    }
+
+    fn children(&self) -> Vec<&dyn Module> {
+        vec![&self.attention, &self.norm_1, &self.linear_1, &self.linear_2, &self.norm_2]
+    }
+}
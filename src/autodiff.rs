@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display, sync::Arc};
 
-use crate::{Matrix, error::MatrixError};
+use crate::{Float, Matrix, CheckpointFn, SparseMatrix, RowGrad, anomaly::{self, Pass}, error::MatrixError};
 
 #[derive(Debug, Clone)]
 pub enum BinaryOpType {
@@ -9,75 +9,331 @@ pub enum BinaryOpType {
     Sub,
     Div,
     MatMul,
+    Maximum,
+    Minimum,
 }
 
 #[derive(Debug, Clone)]
-pub enum UnaryOpType {
+pub enum UnaryOpType<T = f32> {
     Transpose,
     Sigmoid,
     Broadcast,
-    Sum
+    Sum,
+    Exp,
+    Ln,
+    Log1p,
+    Sqrt,
+    Abs,
+    Neg,
+    Sin,
+    Cos,
+    Tanh,
+    Reciprocal,
+    Clamp(T, T),
+    Reshape,
+    Max,
+    Min,
+    /// element `k` of the result is element `map[k]` of the row-major data of the input
+    Index(Arc<Vec<usize>>),
+    /// element `k` of the input is added to element `map[k]` of the row-major data of the result
+    Scatter(Arc<Vec<usize>>),
+    /// same as `Index`, for a lookup into a table whose gradient is kept row-sparse
+    Lookup(Arc<Vec<usize>>),
 }
 
 #[derive(Debug, Clone)]
 pub enum BinaryScalarOpType {
     MulScalar,
-    Powf32
+    Powf32,
+    AddScalar,
+    SubScalar,
+    DivScalar,
 }
 
 #[derive(Debug, Clone)]
-pub enum Operator {
-    Binary(Matrix, Matrix, BinaryOpType),
-    BinaryScalar(Matrix, f32, BinaryScalarOpType),
-    Unary(Matrix, UnaryOpType)
+pub enum Operator<T = f32> {
+    Binary(Matrix<T>, Matrix<T>, BinaryOpType),
+    BinaryScalar(Matrix<T>, T, BinaryScalarOpType),
+    Unary(Matrix<T>, UnaryOpType<T>),
+    Checkpoint(Vec<Matrix<T>>, CheckpointFn<T>),
+    Concat(Vec<Matrix<T>>, usize),
+    SparseMatMul(SparseMatrix<T>, Matrix<T>)
 }
 
-impl Operator {
-    pub fn to_string(&self) -> String {
+impl<T> Operator<T> {
+    /// Returns the input matrices of this operator, in argument order.
+    pub fn operands(&self) -> Vec<&Matrix<T>> {
         match self {
-            Self::Binary(_, _, BinaryOpType::Add) => "Add".to_string(),
-            Self::Binary(_, _, BinaryOpType::Sub) => "Sub".to_string(),
-            Self::Binary(_, _, BinaryOpType::Mul) => "Mul".to_string(),
-            Self::Binary(_, _, BinaryOpType::Div) => "Div".to_string(),
-            Self::Binary(_, _, BinaryOpType::MatMul) => "MatMul".to_string(),
+            Self::Binary(lhs, rhs, _) => vec![lhs, rhs],
+            Self::Unary(mat, _) |
+            Self::BinaryScalar(mat, _, _) |
+            Self::SparseMatMul(_, mat) => vec![mat],
+            Self::Checkpoint(inputs, _) |
+            Self::Concat(inputs, _) => inputs.iter().collect(),
+        }
+    }
+}
+
+impl<T> Display for Operator<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Binary(_, _, BinaryOpType::Add) => "Add",
+            Self::Binary(_, _, BinaryOpType::Sub) => "Sub",
+            Self::Binary(_, _, BinaryOpType::Mul) => "Mul",
+            Self::Binary(_, _, BinaryOpType::Div) => "Div",
+            Self::Binary(_, _, BinaryOpType::MatMul) => "MatMul",
+            Self::Binary(_, _, BinaryOpType::Maximum) => "Maximum",
+            Self::Binary(_, _, BinaryOpType::Minimum) => "Minimum",
             
-            Self::BinaryScalar(_, _, BinaryScalarOpType::MulScalar) => "MulScalar".to_string(),
-            Self::BinaryScalar(_, _, BinaryScalarOpType::Powf32) => "MulScalar".to_string(),
+            Self::BinaryScalar(_, _, BinaryScalarOpType::MulScalar) => "MulScalar",
+            Self::BinaryScalar(_, _, BinaryScalarOpType::Powf32) => "Powf32",
+            Self::BinaryScalar(_, _, BinaryScalarOpType::AddScalar) => "AddScalar",
+            Self::BinaryScalar(_, _, BinaryScalarOpType::SubScalar) => "SubScalar",
+            Self::BinaryScalar(_, _, BinaryScalarOpType::DivScalar) => "DivScalar",
             
-            Self::Unary(_, UnaryOpType::Sigmoid) => "Sigmoid".to_string(),
-            Self::Unary(_, UnaryOpType::Transpose) => "Transpose".to_string(),
-            Self::Unary(_, UnaryOpType::Broadcast) => "Broadcast".to_string(),
-            Self::Unary(_, UnaryOpType::Sum) => "Sum".to_string(),
-        }
-    } 
+            Self::Unary(_, UnaryOpType::Sigmoid) => "Sigmoid",
+            Self::Unary(_, UnaryOpType::Transpose) => "Transpose",
+            Self::Unary(_, UnaryOpType::Broadcast) => "Broadcast",
+            Self::Unary(_, UnaryOpType::Sum) => "Sum",
+            Self::Unary(_, UnaryOpType::Exp) => "Exp",
+            Self::Unary(_, UnaryOpType::Ln) => "Ln",
+            Self::Unary(_, UnaryOpType::Log1p) => "Log1p",
+            Self::Unary(_, UnaryOpType::Sqrt) => "Sqrt",
+            Self::Unary(_, UnaryOpType::Abs) => "Abs",
+            Self::Unary(_, UnaryOpType::Neg) => "Neg",
+            Self::Unary(_, UnaryOpType::Sin) => "Sin",
+            Self::Unary(_, UnaryOpType::Cos) => "Cos",
+            Self::Unary(_, UnaryOpType::Tanh) => "Tanh",
+            Self::Unary(_, UnaryOpType::Reciprocal) => "Reciprocal",
+            Self::Unary(_, UnaryOpType::Clamp(_, _)) => "Clamp",
+            Self::Unary(_, UnaryOpType::Reshape) => "Reshape",
+            Self::Unary(_, UnaryOpType::Max) => "Max",
+            Self::Unary(_, UnaryOpType::Min) => "Min",
+            Self::Unary(_, UnaryOpType::Index(_)) => "Index",
+            Self::Unary(_, UnaryOpType::Scatter(_)) => "Scatter",
+            Self::Unary(_, UnaryOpType::Lookup(_)) => "Lookup",
+
+            Self::Checkpoint(_, _) => "Checkpoint",
+            Self::Concat(_, _) => "Concat",
+            Self::SparseMatMul(_, _) => "SparseMatMul",
+        };
+        write!(f, "{}", name)
+    }
 }
 
+/// Gradients by matrix id. The gradient of a leaf that is only used through row lookups,
+/// such as the table of an `Embedding`, is kept row-sparse and returned by `get_rows`
+/// instead of `get`, unless the leaf also receives a dense gradient.
 #[derive(Debug)]
-pub struct GradMap(HashMap<usize, Matrix>);
+pub struct GradMap<T = f32>(HashMap<usize, Matrix<T>>, HashMap<usize, RowGrad<T>>);
+
+/// Summary statistics over the gradients of a set of parameters.
+/// `max` and `min` ignore non-finite values and are `NaN` when no finite value exists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradStats<T = f32> {
+    pub norm: T,
+    pub max: T,
+    pub min: T,
+    pub nan_count: usize,
+    pub inf_count: usize,
+}
 
-impl GradMap {
+impl<T: Float> Default for GradMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Float> GradMap<T> {
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self(HashMap::new(), HashMap::new())
     }
 
-    pub fn get(&self, id: usize) -> Option<&Matrix> {
+    pub fn get(&self, id: usize) -> Option<&Matrix<T>> {
         self.0.get(&id)
     }
 
-    pub fn insert(&mut self, mat: &Matrix, grad: Matrix) -> Option<Matrix> {
+    /// Row-sparse gradient of the matrix with id `id`, if it has one.
+    pub fn get_rows(&self, id: usize) -> Option<&RowGrad<T>> {
+        self.1.get(&id)
+    }
+
+    /// Gradient of the matrix with id `id` as a dense matrix, whether it is kept dense
+    /// or row-sparse.
+    pub fn get_dense(&self, id: usize) -> Option<Matrix<T>> {
+        self.get(id)
+            .cloned()
+            .or_else(|| self.get_rows(id).map(RowGrad::to_dense))
+    }
+
+    // the elements of the gradient of the matrix with id `id`, the looked up rows only if row-sparse
+    fn values(&self, id: usize) -> Option<&[T]> {
+        self.get(id)
+            .map(|grad| grad.data().as_slice())
+            .or_else(|| self.get_rows(id).map(RowGrad::values))
+    }
+
+    pub fn insert(&mut self, mat: &Matrix<T>, grad: Matrix<T>) -> Option<Matrix<T>> {
         self.0.insert(mat.id(), grad)
     }
 
-    pub fn remove(&mut self, mat: &Matrix) -> Option<Matrix> {
+    pub fn remove(&mut self, mat: &Matrix<T>) -> Option<Matrix<T>> {
         self.0.remove(&mat.id())
     }
 
-    pub fn or_insert(&mut self, mat: &Matrix) -> &mut Matrix {
+    /// L2 norm of the gradient of `mat`, if it has one.
+    pub fn norm(&self, mat: &Matrix<T>) -> Option<T> {
+        self.values(mat.id())
+            .map(|values| values.iter().map(|&x| x * x).sum::<T>().sqrt())
+    }
+
+    /// L2 norm of the gradient of each of `params`, keyed by matrix id.
+    /// Parameters without a gradient are skipped.
+    pub fn norms(&self, params: &[&Matrix<T>]) -> Vec<(usize, T)> {
+        params
+            .iter()
+            .filter_map(|mat| self.norm(mat).map(|norm| (mat.id(), norm)))
+            .collect()
+    }
+
+    /// L2 norm of the gradients of all `params` taken together as one vector.
+    pub fn global_norm(&self, params: &[&Matrix<T>]) -> T {
+        self.norms(params)
+            .iter()
+            .map(|&(_, norm)| norm * norm)
+            .sum::<T>()
+            .sqrt()
+    }
+
+    /// Statistics of the gradients of `params`. Those of a row-sparse gradient only cover
+    /// the rows it holds.
+    pub fn stats(&self, params: &[&Matrix<T>]) -> GradStats<T> {
+        let mut stats = GradStats { 
+            norm: self.global_norm(params), 
+            max: T::NAN, 
+            min: T::NAN, 
+            nan_count: 0, 
+            inf_count: 0 
+        };
+
+        for values in params.iter().filter_map(|mat| self.values(mat.id())) {
+            for &x in values.iter() {
+                if x.is_nan() {
+                    stats.nan_count += 1;
+                } else if x.is_infinite() {
+                    stats.inf_count += 1;
+                } else {
+                    // max and min return the non-NaN operand
+                    stats.max = stats.max.max(x);
+                    stats.min = stats.min.min(x);
+                }
+            }
+        }
+        stats
+    }
+
+    /// Clamps every element of the gradients of `params` into `[min, max]`.
+    /// Panics unless `min <= max`, in particular if either is NaN.
+    pub fn clip_value(&mut self, params: &[&Matrix<T>], min: T, max: T) {
+        assert!(min <= max, "invalid clipping range [{}, {}]", min, max);
+        for mat in params.iter() {
+            if let Some(grad) = self.0.get_mut(&mat.id()) {
+                let data = grad.data().iter().map(|x| x.clamp(min, max)).collect();
+                *grad = Matrix::from_vec(data, grad.shape(), grad.requires_grad());
+            }
+            if let Some(rows) = self.1.get_mut(&mat.id()) {
+                rows.map_values(|x| x.clamp(min, max));
+            }
+        }
+    }
+
+    /// Rescales the gradients of `params` such that their global L2 norm is at most `max_norm`.
+    /// Returns the global norm before clipping.
+    pub fn clip_norm(&mut self, params: &[&Matrix<T>], max_norm: T) -> T {
+        let norm = self.global_norm(params);
+        if norm > max_norm {
+            let scale = max_norm / (norm + T::from_f64(1e-6));
+            for mat in params.iter() {
+                if let Some(grad) = self.0.get_mut(&mat.id()) {
+                    let data = grad.data().iter().map(|&x| x * scale).collect();
+                    *grad = Matrix::from_vec(data, grad.shape(), grad.requires_grad());
+                }
+                if let Some(rows) = self.1.get_mut(&mat.id()) {
+                    rows.map_values(|x| x * scale);
+                }
+            }
+        }
+        norm
+    }
+
+    /// Adds `grad` to the gradient of `mat`.
+    pub fn accumulate(&mut self, mat: &Matrix<T>, grad: &Matrix<T>) -> Result<(), MatrixError> {
+        let sum_grad = self.or_insert(mat);
+        *sum_grad = sum_grad.add(grad)?;
+        Ok(())
+    }
+
+    /// Adds the row-sparse gradient `rows` to the gradient of `mat`, which stays row-sparse
+    /// unless `mat` already has a dense gradient.
+    pub(crate) fn accumulate_rows(&mut self, mat: &Matrix<T>, rows: RowGrad<T>) {
+        use std::collections::hash_map::Entry;
+        if let Some(grad) = self.0.get_mut(&mat.id()) {
+            *grad = rows.add_to(grad);
+            return;
+        }
+        match self.1.entry(mat.id()) {
+            Entry::Occupied(mut entry) => entry.get_mut().add(&rows),
+            Entry::Vacant(entry) => {
+                entry.insert(rows);
+            }
+        }
+    }
+
+    /// Adds every gradient of `other` to the gradient of the same matrix in `self`,
+    /// e.g. to combine the gradients of several shards of a batch.
+    pub fn merge(&mut self, other: GradMap<T>) -> Result<(), MatrixError> {
+        use std::collections::hash_map::Entry;
+        for (id, grad) in other.0.into_iter() {
+            match self.0.entry(id) {
+                Entry::Occupied(mut entry) => {
+                    let sum = entry.get().add(&grad)?;
+                    entry.insert(sum);
+                },
+                Entry::Vacant(entry) => {
+                    let grad = match self.1.remove(&id) {
+                        Some(rows) => rows.add_to(&grad),
+                        None => grad
+                    };
+                    entry.insert(grad);
+                }
+            }
+        }
+        for (id, rows) in other.1.into_iter() {
+            if let Some(grad) = self.0.get_mut(&id) {
+                *grad = rows.add_to(grad);
+                continue;
+            }
+            match self.1.entry(id) {
+                Entry::Occupied(mut entry) => entry.get_mut().add(&rows),
+                Entry::Vacant(entry) => {
+                    entry.insert(rows);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn or_insert(&mut self, mat: &Matrix<T>) -> &mut Matrix<T> {
         use std::collections::hash_map::Entry;
         let grad = match self.0.entry(mat.id()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
+                // a row-sparse gradient becomes dense once a dense gradient is added to it
                 let grad = Matrix::zeros(mat.shape(), mat.requires_grad());
+                let grad = match self.1.remove(&mat.id()) {
+                    Some(rows) => rows.add_to(&grad),
+                    None => grad
+                };
                 entry.insert(grad)
             }
         };
@@ -85,28 +341,42 @@ impl GradMap {
     }
 }
 
-fn visit<'a>(
-    node: &'a Matrix, 
-    nodes: Vec<&'a Matrix>, 
+// constant matrix obtained by applying f to every element of mat, used for local derivatives
+fn elementwise<T: Float, F: Fn(T) -> T>(mat: &Matrix<T>, f: F) -> Matrix<T> {
+    let data = mat.data().iter().map(|&x| f(x)).collect();
+    Matrix::from_vec(data, mat.shape(), false)
+}
+
+// share of the gradient of max(lhs, rhs) that flows into lhs, ties are split evenly
+fn max_mask<T: Float>(lhs: &Matrix<T>, rhs: &Matrix<T>, lhs_wins: fn(T, T) -> bool) -> Matrix<T> {
+    let data = lhs.data()
+        .iter()
+        .zip(rhs.data().iter())
+        .map(|(&l, &r)| if l == r { T::from_f64(0.5) } else if lhs_wins(l, r) { T::one() } else { T::zero() })
+        .collect();
+    Matrix::from_vec(data, lhs.shape(), false)
+}
+
+// orders the nodes with an op, and collects the leaves with hooks along the way
+fn visit<'a, T: Float>(
+    node: &'a Matrix<T>, 
+    nodes: Vec<&'a Matrix<T>>, 
+    hooked_leaves: &mut Vec<&'a Matrix<T>>,
     already_seen: &mut HashMap<usize, bool>
-) -> Vec<&'a Matrix> {
+) -> Vec<&'a Matrix<T>> {
 
-    already_seen.insert(node.id(), true);
+    if already_seen.insert(node.id(), true).is_some() {
+        return nodes;
+    }
 
     let mut nodes = if let Some(op) = node.op() {
-        match op {
-            Operator::Binary(lhs, rhs, _) => {
-                let nodes = visit(lhs, nodes, already_seen);
-                let nodes = visit(rhs, nodes, already_seen);
-                nodes
-            },
-            Operator::Unary(mat, _) |
-            Operator::BinaryScalar(mat, _, _) => {
-                let nodes = visit(mat, nodes, already_seen);
-                nodes
-            }
-        }
+        op.operands()
+            .into_iter()
+            .fold(nodes, |nodes, mat| visit(mat, nodes, hooked_leaves, already_seen))
     } else {
+        if node.requires_grad() && !node.hooks().is_empty() {
+            hooked_leaves.push(node);
+        }
         return nodes;
     };
     nodes.push(node);
@@ -114,26 +384,62 @@ fn visit<'a>(
 }
 
 
-impl Matrix {
+impl<T: Float> Matrix<T> {
+
+    /// Sums the gradient of a broadcast result back into the `shape` it was broadcast from,
+    /// folding every dimension that was broadcast from size 1.
+    fn reduce_to(&self, shape: (usize, usize)) -> Result<Matrix<T>, MatrixError> {
+        let (rows, cols) = self.shape();
+        let mut grad = self.clone();
+        if shape.0 == 1 && rows != 1 {
+            grad = grad.sum(0)?;
+        }
+        if shape.1 == 1 && cols != 1 {
+            grad = grad.sum(1)?;
+        }
+        if grad.shape() != shape {
+            return Err(MatrixError::BroadCastError { 
+                got_shape: shape, 
+                expected_shape: self.shape() 
+            });
+        }
+        Ok(grad)
+    }
+
+    pub fn topological_sort(&self) -> Vec<&Matrix<T>> {
+        self.sort_with_hooked_leaves().0
+    }
 
-    pub fn topological_sort(&self) -> Vec<&Matrix> {
-        let mut sorted_nodes = visit(self, vec![], &mut HashMap::new()); 
+    fn sort_with_hooked_leaves(&self) -> (Vec<&Matrix<T>>, Vec<&Matrix<T>>) {
+        let mut hooked_leaves = vec![];
+        let mut sorted_nodes = visit(self, vec![], &mut hooked_leaves, &mut HashMap::new()); 
         sorted_nodes.reverse();
-        sorted_nodes
+        (sorted_nodes, hooked_leaves)
+    }
+
+    pub fn backward(&self) -> Result<GradMap<T>, MatrixError> {
+        self.backward_with(Matrix::ones(self.shape(), self.requires_grad()))
     }
 
-    pub fn backward(&self) -> Result<GradMap, MatrixError> {
+    /// Backpropagates starting from `grad` as the gradient of this matrix,
+    /// instead of a matrix of ones.
+    pub fn backward_with(&self, grad: Matrix<T>) -> Result<GradMap<T>, MatrixError> {
         
-        let sorted_nodes = self.topological_sort();
+        // report anomalies of the forward pass before propagating anything
+        anomaly::raise(self)?;
+        let _guard = anomaly::SuspendGuard::new();
+        let check_anomalies = anomaly::is_anomaly_detection_enabled();
+
+        let (sorted_nodes, hooked_leaves) = self.sort_with_hooked_leaves();
         // println!("{:?}", sorted_nodes.clone().into_iter().map(|x| x.id()).collect::<Vec<usize>>());
         let mut grads = GradMap::new();
-        grads.insert(self, Matrix::ones(self.shape(), self.requires_grad()));
+        grads.insert(self, grad);
 
         for node in sorted_nodes.iter() {
             if !node.requires_grad() {
                 continue;
             }
-            let grad = grads.remove(node).unwrap();
+            let grad = node.apply_hooks(grads.remove(node).unwrap())?;
             if let Some(op) = node.op() {
                 match op {
                     Operator::Binary(lhs, rhs, BinaryOpType::MatMul) => {
@@ -142,36 +448,27 @@ impl Matrix {
                         // A' += C' @ B
                         // B' += A.T @ C'
                         let lhs_grad = grad.matmul(&rhs.t())?;
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&lhs_grad)?;
+                        grads.accumulate(lhs, &lhs_grad)?;
 
                         let rhs_grad = lhs.t().matmul(&grad)?;
-                        let rhs_sum_grad = grads.or_insert(rhs);
-                        *rhs_sum_grad = rhs_sum_grad.add(&rhs_grad)?;
+                        grads.accumulate(rhs, &rhs_grad)?;
                     },
                     Operator::Binary(lhs, rhs, BinaryOpType::Add) => {
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&grad)?;
-
-                        let rhs_sum_grad = grads.or_insert(rhs);
-                        *rhs_sum_grad = rhs_sum_grad.add(&grad)?;
+                        grads.accumulate(lhs, &grad)?;
+                        grads.accumulate(rhs, &grad)?;
                     },
                     Operator::Binary(lhs, rhs, BinaryOpType::Sub) => {
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&grad)?;
+                        grads.accumulate(lhs, &grad)?;
 
-                        let rhs_grad = grad.mul(&Matrix::fill(rhs.shape(), -1., rhs.requires_grad()))?;
-                        let rhs_sum_grad = grads.or_insert(rhs);
-                        *rhs_sum_grad = rhs_sum_grad.add(&rhs_grad)?;
+                        let rhs_grad = grad.mul(&Matrix::fill(rhs.shape(), -T::one(), rhs.requires_grad()))?;
+                        grads.accumulate(rhs, &rhs_grad)?;
                     },
                     Operator::Binary(lhs, rhs, BinaryOpType::Mul) => {
-                        let lhs_grad = grad.mul(&rhs)?;
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&lhs_grad)?;
+                        let lhs_grad = grad.mul(rhs)?;
+                        grads.accumulate(lhs, &lhs_grad)?;
 
-                        let rhs_grad = grad.mul(&lhs)?;
-                        let rhs_sum_grad = grads.or_insert(rhs);
-                        *rhs_sum_grad = rhs_sum_grad.add(&rhs_grad)?;
+                        let rhs_grad = grad.mul(lhs)?;
+                        grads.accumulate(rhs, &rhs_grad)?;
                     },
                     Operator::Binary(lhs, rhs, BinaryOpType::Div) => {
                         // c = a/b
@@ -180,26 +477,22 @@ impl Matrix {
                         // 
                         // x/5 = 1/5 * x = 1/5
                         // 5/x = 5 * 1/x = 5 * x^(-2) = 5 * -1 * x^(-2) = -5 * x^(-2) => -5/x^2
-                        let lhs_grad = grad.mul(&Matrix::ones(rhs.shape(), rhs.requires_grad()).div(&rhs)?)?;
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&lhs_grad)?;
+                        let lhs_grad = grad.mul(&Matrix::ones(rhs.shape(), rhs.requires_grad()).div(rhs)?)?;
+                        grads.accumulate(lhs, &lhs_grad)?;
 
-                        let negative_lhs = lhs.mul(&Matrix::fill(lhs.shape(), -1., lhs.requires_grad()))?;
-                        let rhs_squared = rhs.mul(&rhs)?;
+                        let negative_lhs = lhs.mul(&Matrix::fill(lhs.shape(), -T::one(), lhs.requires_grad()))?;
+                        let rhs_squared = rhs.mul(rhs)?;
                         let rhs_grad = grad.mul(&negative_lhs.div(&rhs_squared)?)?;
-                        let rhs_sum_grad = grads.or_insert(rhs);
-                        *rhs_sum_grad = rhs_sum_grad.add(&rhs_grad)?;
+                        grads.accumulate(rhs, &rhs_grad)?;
                     },
                     Operator::Unary(mat, UnaryOpType::Transpose) => {
                         let mat_grad = grad.t();
-                        let mat_sum_grad = grads.or_insert(mat);
-                        *mat_sum_grad = mat_sum_grad.add(&mat_grad)?;
+                        grads.accumulate(mat, &mat_grad)?;
                     },
                     Operator::Unary(mat, UnaryOpType::Sigmoid) => {
-                        let sigmoid_der = node.mul(&Matrix::ones(node.shape(), node.requires_grad()).sub(&node)?)?;                        
+                        let sigmoid_der = node.mul(&Matrix::ones(node.shape(), node.requires_grad()).sub(node)?)?;                        
                         let mat_grad = grad.mul(&sigmoid_der)?;
-                        let mat_sum_grad = grads.or_insert(mat);
-                        *mat_sum_grad = mat_sum_grad.add(&mat_grad)?;
+                        grads.accumulate(mat, &mat_grad)?;
                     },
                     Operator::Unary(mat, UnaryOpType::Broadcast) => {
                         
@@ -223,33 +516,152 @@ impl Matrix {
                         //  [2.0, 2.0]  =>   
                         //  [3.0, 3.0]    
 
-                        let mat_grad = match mat.shape() {
-                            (_, 1) => grad.sum(0)?,
-                            (1, _) => grad.sum(1)?,
-                            _ => grad.clone()
-                        }; 
+                        let mat_grad = grad.reduce_to(mat.shape())?;
 
-                        let mat_sum_grad = grads.or_insert(mat);
-                        *mat_sum_grad = mat_sum_grad.add(&mat_grad)?;
+                        grads.accumulate(mat, &mat_grad)?;
                     },
                     Operator::Unary(mat, UnaryOpType::Sum) => {
-                        todo!()
+                        // every element of mat contributes once to the sum it was folded into
+                        let mat_grad = grad.broadcast_as(mat.shape())?;
+                        grads.accumulate(mat, &mat_grad)?;
                     }
                     Operator::BinaryScalar(lhs, rhs, BinaryScalarOpType::MulScalar) => {
                         let lhs_grad = grad.mul_scalar(*rhs);
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&lhs_grad)?;
+                        grads.accumulate(lhs, &lhs_grad)?;
                     },
                     Operator::BinaryScalar(lhs, rhs, BinaryScalarOpType::Powf32) => {
-                        let lhs_grad = grad.mul_scalar(*rhs).mul(&lhs.powf((*rhs)-1.))?;
-                        let lhs_sum_grad = grads.or_insert(lhs);
-                        *lhs_sum_grad = lhs_sum_grad.add(&lhs_grad)?;
+                        let lhs_grad = grad.mul_scalar(*rhs).mul(&lhs.powf((*rhs) - T::one()))?;
+                        grads.accumulate(lhs, &lhs_grad)?;
+                    },
+                    Operator::Binary(lhs, rhs, op @ (BinaryOpType::Maximum | BinaryOpType::Minimum)) => {
+                        let lhs_mask = match op {
+                            BinaryOpType::Maximum => max_mask(lhs, rhs, |l, r| l > r),
+                            _ => max_mask(lhs, rhs, |l, r| l < r),
+                        };
+                        let rhs_mask = elementwise(&lhs_mask, |x| T::one() - x);
+                        grads.accumulate(lhs, &grad.mul(&lhs_mask)?)?;
+                        grads.accumulate(rhs, &grad.mul(&rhs_mask)?)?;
                     },
+                    Operator::BinaryScalar(lhs, _, BinaryScalarOpType::AddScalar | BinaryScalarOpType::SubScalar) => {
+                        grads.accumulate(lhs, &grad)?;
+                    },
+                    Operator::BinaryScalar(lhs, rhs, BinaryScalarOpType::DivScalar) => {
+                        grads.accumulate(lhs, &grad.div_scalar(*rhs))?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Exp) => {
+                        // (e^x)' = e^x
+                        grads.accumulate(mat, &grad.mul(node)?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Ln) => {
+                        grads.accumulate(mat, &grad.div(mat)?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Log1p) => {
+                        grads.accumulate(mat, &grad.div(&mat.add_scalar(T::one()))?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Sqrt) => {
+                        // (x^(1/2))' = 1/(2 * x^(1/2))
+                        grads.accumulate(mat, &grad.div(&node.mul_scalar(T::from_f64(2.)))?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Abs) => {
+                        let sign = elementwise(mat, |x| if x == T::zero() { T::zero() } else { x.signum() });
+                        grads.accumulate(mat, &grad.mul(&sign)?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Neg) => {
+                        grads.accumulate(mat, &grad.neg())?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Sin) => {
+                        grads.accumulate(mat, &grad.mul(&mat.cos())?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Cos) => {
+                        grads.accumulate(mat, &grad.mul(&mat.sin().neg())?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Tanh) => {
+                        // tanh' = 1 - tanh^2
+                        grads.accumulate(mat, &grad.mul(&node.powf(T::from_f64(2.)).neg().add_scalar(T::one()))?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Reciprocal) => {
+                        // (1/x)' = -1/x^2
+                        grads.accumulate(mat, &grad.mul(&node.powf(T::from_f64(2.)).neg())?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Clamp(min, max)) => {
+                        let inside = elementwise(mat, |x| if *min <= x && x <= *max { T::one() } else { T::zero() });
+                        grads.accumulate(mat, &grad.mul(&inside)?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Reshape) => {
+                        grads.accumulate(mat, &grad.reshape(mat.shape())?)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Max | UnaryOpType::Min) => {
+                        // the gradient flows into the extreme elements, split evenly among ties
+                        let extremes = node.detach().broadcast_as(mat.shape())?;
+                        let data = mat.data()
+                            .iter()
+                            .zip(extremes.data().iter())
+                            .map(|(x, m)| if x == m { T::one() } else { T::zero() })
+                            .collect();
+                        let mask = Matrix::from_vec(data, mat.shape(), false);
+                        let counts = mask.reduce_to(node.shape())?.broadcast_as(mat.shape())?;
+                        let mat_grad = grad.broadcast_as(mat.shape())?.mul(&mask.div(&counts)?)?;
+                        grads.accumulate(mat, &mat_grad)?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Index(map)) => {
+                        grads.accumulate(mat, &grad.scatter_map(map.clone(), mat.shape()))?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Scatter(map)) => {
+                        grads.accumulate(mat, &grad.index_map(map.clone(), mat.shape()))?;
+                    },
+                    Operator::Unary(mat, UnaryOpType::Lookup(map)) => {
+                        // only a leaf without hooks keeps a row-sparse gradient, the gradient of
+                        // anything else is propagated further or passed to its hooks as a matrix
+                        if mat.op().is_none() && mat.hooks().is_empty() {
+                            grads.accumulate_rows(mat, RowGrad::scatter(mat.shape(), map, &grad));
+                        } else {
+                            grads.accumulate(mat, &grad.scatter_map(map.clone(), mat.shape()))?;
+                        }
+                    },
+                    Operator::Concat(inputs, axis) => {
+                        let mut offset = 0;
+                        for mat in inputs.iter() {
+                            let (rows, cols) = mat.shape();
+                            let mat_grad = match axis {
+                                0 => grad.slice_rows(offset, offset + rows)?,
+                                _ => grad.slice_cols(offset, offset + cols)?,
+                            };
+                            offset += if *axis == 0 { rows } else { cols };
+                            grads.accumulate(mat, &mat_grad)?;
+                        }
+                    },
+                    Operator::SparseMatMul(lhs, rhs) => {
+                        // C = A @ B with constant A
+                        //
+                        // B' += A.T @ C'
+                        grads.accumulate(rhs, &lhs.matmul_grad(&grad)?)?;
+                    },
+                    Operator::Checkpoint(inputs, f) => {
+                        f.backward(inputs, &grad, &mut grads)?;
+                    },
+                }
+
+                if check_anomalies {
+                    let non_finite = op.operands()
+                        .iter()
+                        .filter_map(|mat| grads.values(mat.id()))
+                        .any(|values| values.iter().any(|x| !x.is_finite()));
+                    if non_finite {
+                        return Err(anomaly::anomaly_error(node, Pass::Backward));
+                    }
                 }
             }
             grads.insert(node, grad);
         }
 
+        // gradients of leaves are complete once every node has been processed
+        for leaf in hooked_leaves {
+            if let Some(grad) = grads.remove(leaf) {
+                let grad = leaf.apply_hooks(grad)?;
+                grads.insert(leaf, grad);
+            }
+        }
+
         Ok(grads)
     }
 }
\ No newline at end of file
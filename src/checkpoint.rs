@@ -0,0 +1,90 @@
+use std::{collections::HashSet, fmt::Debug, sync::Arc};
+
+use crate::{Float, GradMap, Matrix, Operator, anomaly, error::MatrixError};
+
+type CheckpointClosure<T> = dyn Fn(&[Matrix<T>]) -> Result<Matrix<T>, MatrixError> + Send + Sync;
+
+/// The sub-computation of a checkpoint, which is run again during `backward`.
+pub struct CheckpointFn<T = f32>(Arc<CheckpointClosure<T>>);
+
+impl<T> Clone for CheckpointFn<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Debug for CheckpointFn<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CheckpointFn")
+    }
+}
+
+impl<T: Float> CheckpointFn<T> {
+    pub(crate) fn backward(&self, inputs: &[Matrix<T>], grad: &Matrix<T>, grads: &mut GradMap<T>) -> Result<(), MatrixError> {
+        
+        // recompute the sub-graph from fresh leaves and backpropagate through it
+        let leaves = inputs
+            .iter()
+            .map(|mat| mat.as_leaf(mat.requires_grad()))
+            .collect::<Vec<Matrix<T>>>();
+        let out = (self.0)(&leaves)?;
+        let inner_grads = out.backward_with(grad.clone())?;
+
+        for (input, leaf) in inputs.iter().zip(leaves.iter()) {
+            if let Some(leaf_grad) = inner_grads.get_dense(leaf.id()) {
+                grads.accumulate(input, &leaf_grad)?;
+            }
+        }
+
+        // parameters captured by the closure are leaves of the recomputed graph as well
+        let leaf_ids = leaves.iter().map(|leaf| leaf.id()).collect::<HashSet<usize>>();
+        for node in out.graph_nodes() {
+            if node.op().is_some() || !node.requires_grad() || leaf_ids.contains(&node.id()) {
+                continue;
+            }
+            if let Some(node_grad) = inner_grads.get(node.id()) {
+                grads.accumulate(node, node_grad)?;
+            }
+            if let Some(rows) = inner_grads.get_rows(node.id()) {
+                grads.accumulate_rows(node, rows.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Float> Matrix<T> {
+
+    /// Runs `f` on `inputs` without keeping the intermediate results of `f` alive.
+    /// Only the inputs and the output are stored in the graph; `f` is run a second 
+    /// time during `backward` to obtain the gradients, trading compute for memory.
+    /// 
+    /// Everything `f` depends on that needs a gradient must either be passed in `inputs`
+    /// or be a leaf, such as the weights of a layer, captured by the closure. A captured
+    /// matrix that is the result of an op gets no gradient of its own in the `GradMap`;
+    /// its gradient only reaches the leaves it depends on, and hooks on it are not run.
+    pub fn checkpoint<F>(inputs: &[&Matrix<T>], f: F) -> Result<Matrix<T>, MatrixError>
+    where F: Fn(&[Matrix<T>]) -> Result<Matrix<T>, MatrixError> + Send + Sync + 'static 
+    {
+        let detached = inputs
+            .iter()
+            .map(|mat| mat.detach())
+            .collect::<Vec<Matrix<T>>>();
+        
+        // the graph built by f is dropped at the end of this scope
+        let out = f(&detached)?;
+
+        let req_grad = out.requires_grad() || inputs.iter().any(|mat| mat.requires_grad());
+        let inputs = inputs
+            .iter()
+            .map(|&mat| mat.clone())
+            .collect::<Vec<Matrix<T>>>();
+        let op = Operator::Checkpoint(inputs, CheckpointFn(Arc::new(f)));
+
+        // anomalies inside f are carried by its output only
+        anomaly::raise(&out)?;
+        let mat = Matrix::from_op(out.data().clone(), out.shape(), op, req_grad);
+        anomaly::raise(&mat)?;
+        Ok(mat)
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{Matrix, Module, Parameter, error::MatrixError};
+
+fn prefixed(mut module: Box<dyn Module>, prefix: &str) -> Box<dyn Module> {
+    for param in module.parameters_mut() {
+        param.add_prefix(prefix);
+    }
+    for buffer in module.buffers_mut() {
+        buffer.add_prefix(prefix);
+    }
+    module
+}
+
+/// Skip connection computing `body(x) + shortcut(x)`, where the shortcut is the identity
+/// unless set with `with_shortcut`, e.g. a `Dense` projection when the body changes the
+/// number of features.
+///
+/// The parameters of the body are prefixed with `body`, those of the shortcut with `shortcut`.
+#[derive(Debug, Clone)]
+pub struct Residual {
+    body: Box<dyn Module>,
+    shortcut: Option<Box<dyn Module>>
+}
+
+impl Residual {
+    pub fn new<M: Module + 'static>(body: M) -> Self {
+        Self { body: prefixed(Box::new(body), "body"), shortcut: None }
+    }
+
+    pub fn with_shortcut<M: Module + 'static>(mut self, shortcut: M) -> Self {
+        self.shortcut = Some(prefixed(Box::new(shortcut), "shortcut"));
+        self
+    }
+
+    fn modules(&self) -> Vec<&dyn Module> {
+        std::iter::once(self.body.as_ref())
+            .chain(self.shortcut.as_deref())
+            .collect()
+    }
+
+    fn modules_mut(&mut self) -> Vec<&mut Box<dyn Module>> {
+        std::iter::once(&mut self.body)
+            .chain(self.shortcut.as_mut())
+            .collect()
+    }
+}
+
+impl Module for Residual {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let ys = self.body.forward(xs)?;
+        let skip = match &self.shortcut {
+            Some(shortcut) => shortcut.forward(xs)?,
+            None => xs.clone()
+        };
+        // no broadcasting, a mismatch is a mistake in the model
+        if ys.shape() != skip.shape() {
+            return Err(MatrixError::ShapeMismatchError { a_shape: ys.shape(), b_shape: skip.shape(), op: "residual".to_string() });
+        }
+        ys.add(&skip)
+    }
+
+    fn describe(&self) -> String {
+        "Residual".to_string()
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        self.modules()
+            .into_iter()
+            .flat_map(|module| module.parameters())
+            .collect()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        self.modules_mut()
+            .into_iter()
+            .flat_map(|module| module.parameters_mut())
+            .collect()
+    }
+
+    fn buffers(&self) -> Vec<Parameter> {
+        self.modules()
+            .into_iter()
+            .flat_map(|module| module.buffers())
+            .collect()
+    }
+
+    fn buffers_mut(&mut self) -> Vec<&mut Parameter> {
+        self.modules_mut()
+            .into_iter()
+            .flat_map(|module| module.buffers_mut())
+            .collect()
+    }
+
+    fn set_training(&mut self, training: bool) {
+        for module in self.modules_mut() {
+            module.set_training(training);
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Module> {
+        self.modules()
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        self.modules().into_iter().find_map(|module| module.input_features())
+    }
+}
+
+/// How `Parallel` merges the outputs of its branches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Merge {
+    /// Elementwise sum, the branches must have outputs of the same shape.
+    Add,
+    /// Outputs stacked along the rows (features), in the order of the branches.
+    Concat
+}
+
+/// Feeds the same input into every branch and merges their outputs, e.g. the wide and
+/// the deep part of a wide-and-deep model. The gradients of the branches with respect
+/// to the shared input are summed.
+///
+/// The parameters of the `i`-th branch are prefixed with `i`, e.g. `0.weight`.
+#[derive(Debug, Clone)]
+pub struct Parallel {
+    branches: Vec<Box<dyn Module>>,
+    merge: Merge
+}
+
+impl Parallel {
+    pub fn new(merge: Merge) -> Self {
+        Self { branches: vec![], merge }
+    }
+
+    /// Adds a branch, prefixing the names of its parameters and buffers with its index.
+    pub fn push<M: Module + 'static>(mut self, branch: M) -> Self {
+        let prefix = self.branches.len().to_string();
+        self.branches.push(prefixed(Box::new(branch), &prefix));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.branches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+}
+
+impl Module for Parallel {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let outputs = self.branches
+            .iter()
+            .map(|branch| branch.forward(xs))
+            .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+        let Some(first) = outputs.first() else {
+            return Ok(xs.clone());
+        };
+
+        match self.merge {
+            Merge::Add => outputs[1..].iter().try_fold(first.clone(), |sum, ys| {
+                if ys.shape() != sum.shape() {
+                    return Err(MatrixError::ShapeMismatchError { a_shape: sum.shape(), b_shape: ys.shape(), op: "parallel add".to_string() });
+                }
+                sum.add(ys)
+            }),
+            Merge::Concat => Matrix::concat(&outputs.iter().collect::<Vec<&Matrix>>(), 0)
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("Parallel({:?})", self.merge)
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        self.branches
+            .iter()
+            .flat_map(|branch| branch.parameters())
+            .collect()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        self.branches
+            .iter_mut()
+            .flat_map(|branch| branch.parameters_mut())
+            .collect()
+    }
+
+    fn buffers(&self) -> Vec<Parameter> {
+        self.branches
+            .iter()
+            .flat_map(|branch| branch.buffers())
+            .collect()
+    }
+
+    fn buffers_mut(&mut self) -> Vec<&mut Parameter> {
+        self.branches
+            .iter_mut()
+            .flat_map(|branch| branch.buffers_mut())
+            .collect()
+    }
+
+    fn set_training(&mut self, training: bool) {
+        for branch in self.branches.iter_mut() {
+            branch.set_training(training);
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Module> {
+        self.branches
+            .iter()
+            .map(|branch| branch.as_ref())
+            .collect()
+    }
+
+    // every branch gets the same input
+    fn input_features(&self) -> Option<usize> {
+        self.branches.iter().find_map(|branch| branch.input_features())
+    }
+}
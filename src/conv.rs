@@ -0,0 +1,456 @@
+use std::sync::Arc;
+
+use crate::{Matrix, Module, Parameter, error::MatrixError};
+
+// Feature maps are stored like any other batch: every sample is a column holding
+// its channels one after another, each channel a row-major height x width grid.
+// A 1-D signal is a feature map of height 1.
+
+#[derive(Debug, Clone, Copy)]
+struct Geometry {
+    channels: usize,
+    height: usize,
+    width: usize,
+    kernel: (usize, usize),
+    stride: (usize, usize),
+    padding: (usize, usize),
+    dilation: (usize, usize)
+}
+
+// kernel sizes, strides and dilations of 0 leave no valid window
+fn assert_positive(name: &str, (h, w): (usize, usize)) {
+    assert!(h > 0 && w > 0, "{} must be positive, got {:?}", name, (h, w));
+}
+
+impl Geometry {
+    fn new(channels: usize, (height, width): (usize, usize), kernel: (usize, usize)) -> Self {
+        assert_positive("kernel", kernel);
+        Self { channels, height, width, kernel, stride: (1, 1), padding: (0, 0), dilation: (1, 1) }
+    }
+
+    fn padded(&self) -> (usize, usize) {
+        (self.height + 2 * self.padding.0, self.width + 2 * self.padding.1)
+    }
+
+    fn out_size(&self) -> (usize, usize) {
+        let (height, width) = self.padded();
+        let size = |len: usize, kernel: usize, stride: usize, dilation: usize| {
+            let span = dilation * (kernel - 1) + 1;
+            if len < span { 0 } else { (len - span) / stride + 1 }
+        };
+        (
+            size(height, self.kernel.0, self.stride.0, self.dilation.0),
+            size(width, self.kernel.1, self.stride.1, self.dilation.1)
+        )
+    }
+
+    fn features(&self) -> usize {
+        self.channels * self.height * self.width
+    }
+
+    fn check_input(&self, xs: &Matrix, op: &str) -> Result<(), MatrixError> {
+        let features = self.features();
+        let (out_height, out_width) = self.out_size();
+        if xs.shape().0 != features || out_height == 0 || out_width == 0 {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: (features, xs.shape().1),
+                b_shape: xs.shape(),
+                op: op.to_string()
+            });
+        }
+        Ok(())
+    }
+
+    // row-major position of input element (channel, y, x) of sample n within a padded batch
+    fn position(&self, (height, width): (usize, usize), samples: usize, (c, y, x, n): (usize, usize, usize, usize)) -> usize {
+        ((c * height + y) * width + x) * samples + n
+    }
+
+    // zero padding, scattering every input element into a larger batch of feature maps
+    fn pad(&self, xs: &Matrix) -> Matrix {
+        let samples = xs.shape().1;
+        let (ph, pw) = self.padding;
+        if ph == 0 && pw == 0 {
+            return xs.clone();
+        }
+        let padded = self.padded();
+        let mut map = Vec::with_capacity(xs.data().len());
+        for c in 0..self.channels {
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    for n in 0..samples {
+                        map.push(self.position(padded, samples, (c, y + ph, x + pw, n)));
+                    }
+                }
+            }
+        }
+        xs.scatter_map(Arc::new(map), (self.channels * padded.0 * padded.1, samples))
+    }
+
+    // position of the kernel element (ki, kj) of output location (oy, ox) in the padded input
+    fn tap(&self, samples: usize, (c, oy, ox, ki, kj, n): (usize, usize, usize, usize, usize, usize)) -> usize {
+        let y = oy * self.stride.0 + ki * self.dilation.0;
+        let x = ox * self.stride.1 + kj * self.dilation.1;
+        self.position(self.padded(), samples, (c, y, x, n))
+    }
+
+    /// im2col for convolutions: a `(channels * kh * kw, samples * out_h * out_w)` matrix,
+    /// whose columns are the receptive fields of the output locations of every sample.
+    fn conv_patches(&self, padded: &Matrix) -> Matrix {
+        let samples = padded.shape().1;
+        let (kh, kw) = self.kernel;
+        let (out_height, out_width) = self.out_size();
+        let cols = samples * out_height * out_width;
+
+        let mut map = Vec::with_capacity(self.channels * kh * kw * cols);
+        for c in 0..self.channels {
+            for ki in 0..kh {
+                for kj in 0..kw {
+                    for n in 0..samples {
+                        for oy in 0..out_height {
+                            for ox in 0..out_width {
+                                map.push(self.tap(samples, (c, oy, ox, ki, kj, n)));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        padded.index_map(Arc::new(map), (self.channels * kh * kw, cols))
+    }
+
+    /// im2col for pooling: a `(kh * kw, channels * samples * out_h * out_w)` matrix,
+    /// whose columns are the windows of every channel separately.
+    fn pool_patches(&self, xs: &Matrix) -> Matrix {
+        let samples = xs.shape().1;
+        let (kh, kw) = self.kernel;
+        let (out_height, out_width) = self.out_size();
+        let cols = self.channels * samples * out_height * out_width;
+
+        let mut map = Vec::with_capacity(kh * kw * cols);
+        for ki in 0..kh {
+            for kj in 0..kw {
+                for c in 0..self.channels {
+                    for n in 0..samples {
+                        for oy in 0..out_height {
+                            for ox in 0..out_width {
+                                map.push(self.tap(samples, (c, oy, ox, ki, kj, n)));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        xs.index_map(Arc::new(map), (kh * kw, cols))
+    }
+
+    /// Rearranges the `(channel, sample, location)` ordered elements of `mat` into a batch of feature maps.
+    fn batched(&self, mat: &Matrix, channels: usize, samples: usize) -> Matrix {
+        let (out_height, out_width) = self.out_size();
+        let locations = out_height * out_width;
+        let mut map = Vec::with_capacity(channels * locations * samples);
+        for c in 0..channels {
+            for l in 0..locations {
+                for n in 0..samples {
+                    map.push((c * samples + n) * locations + l);
+                }
+            }
+        }
+        mat.index_map(Arc::new(map), (channels * locations, samples))
+    }
+}
+
+/// 2-D convolution over feature maps of shape `(in_channels, height, width)`, with a
+/// `weight` of shape `(out_channels, in_channels * kh * kw)` and a `bias` of shape
+/// `(out_channels, 1)`. The output holds `out_channels` feature maps of size `output_shape`.
+#[derive(Debug, Clone)]
+pub struct Conv2d {
+    weight: Parameter,
+    bias: Parameter,
+    geometry: Geometry
+}
+
+impl Conv2d {
+    /// Weights are drawn uniformly from `[-1/sqrt(fan_in), 1/sqrt(fan_in)]`.
+    /// Panics if a dimension of `kernel` is 0.
+    pub fn new(in_channels: usize, out_channels: usize, kernel: (usize, usize), input: (usize, usize)) -> Self {
+        let fan_in = in_channels * kernel.0 * kernel.1;
+        let bound = 1. / (fan_in as f32).sqrt();
+        Self {
+            weight: Parameter::new("weight", Matrix::randn(-bound, bound, (out_channels, fan_in), true)),
+            bias: Parameter::new("bias", Matrix::randn(-bound, bound, (out_channels, 1), true)),
+            geometry: Geometry::new(in_channels, input, kernel)
+        }
+    }
+
+    pub fn weight(&self) -> &Parameter {
+        &self.weight
+    }
+
+    pub fn bias(&self) -> &Parameter {
+        &self.bias
+    }
+
+    /// Panics if a dimension of `stride` is 0.
+    pub fn with_stride(mut self, stride: (usize, usize)) -> Self {
+        assert_positive("stride", stride);
+        self.geometry.stride = stride;
+        self
+    }
+
+    /// Zero padding added to both sides of every dimension.
+    pub fn with_padding(mut self, padding: (usize, usize)) -> Self {
+        self.geometry.padding = padding;
+        self
+    }
+
+    /// Panics if a dimension of `dilation` is 0.
+    pub fn with_dilation(mut self, dilation: (usize, usize)) -> Self {
+        assert_positive("dilation", dilation);
+        self.geometry.dilation = dilation;
+        self
+    }
+
+    /// `(out_channels, height, width)` of the output feature maps.
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        let (height, width) = self.geometry.out_size();
+        (self.weight.value().shape().0, height, width)
+    }
+}
+
+impl Module for Conv2d {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        self.geometry.check_input(xs, "conv")?;
+        let patches = self.geometry.conv_patches(&self.geometry.pad(xs));
+        let out = self.weight.value().matmul(&patches)?.add(self.bias.value())?;
+        Ok(self.geometry.batched(&out, self.weight.value().shape().0, xs.shape().1))
+    }
+
+    fn describe(&self) -> String {
+        let g = &self.geometry;
+        let (out_channels, height, width) = self.output_shape();
+        format!(
+            "Conv2d({}x{}x{} -> {}x{}x{}, kernel {:?}, stride {:?}, padding {:?}, dilation {:?})",
+            g.channels, g.height, g.width, out_channels, height, width, g.kernel, g.stride, g.padding, g.dilation
+        )
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.geometry.features())
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        vec![&self.weight, &self.bias]
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weight, &mut self.bias]
+    }
+}
+
+/// 1-D convolution over signals of shape `(in_channels, length)`.
+#[derive(Debug, Clone)]
+pub struct Conv1d(Conv2d);
+
+impl Conv1d {
+    /// Panics if `kernel` is 0.
+    pub fn new(in_channels: usize, out_channels: usize, kernel: usize, length: usize) -> Self {
+        Self(Conv2d::new(in_channels, out_channels, (1, kernel), (1, length)))
+    }
+
+    /// Panics if `stride` is 0.
+    pub fn with_stride(self, stride: usize) -> Self {
+        Self(self.0.with_stride((1, stride)))
+    }
+
+    pub fn with_padding(self, padding: usize) -> Self {
+        Self(self.0.with_padding((0, padding)))
+    }
+
+    /// Panics if `dilation` is 0.
+    pub fn with_dilation(self, dilation: usize) -> Self {
+        Self(self.0.with_dilation((1, dilation)))
+    }
+
+    /// `(out_channels, length)` of the output signals.
+    pub fn output_shape(&self) -> (usize, usize) {
+        let (channels, _, length) = self.0.output_shape();
+        (channels, length)
+    }
+}
+
+impl Module for Conv1d {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        self.0.forward(xs)
+    }
+
+    fn describe(&self) -> String {
+        let g = &self.0.geometry;
+        let (out_channels, length) = self.output_shape();
+        format!(
+            "Conv1d({}x{} -> {}x{}, kernel {}, stride {}, padding {}, dilation {})",
+            g.channels, g.width, out_channels, length, g.kernel.1, g.stride.1, g.padding.1, g.dilation.1
+        )
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        self.0.input_features()
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        self.0.parameters()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        self.0.parameters_mut()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PoolKind {
+    Max,
+    Avg
+}
+
+#[derive(Debug, Clone)]
+struct Pool {
+    geometry: Geometry,
+    kind: PoolKind
+}
+
+impl Pool {
+    fn new(kind: PoolKind, channels: usize, input: (usize, usize), kernel: (usize, usize), stride: (usize, usize)) -> Self {
+        let mut geometry = Geometry::new(channels, input, kernel);
+        assert_positive("stride", stride);
+        geometry.stride = stride;
+        Self { geometry, kind }
+    }
+
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        self.geometry.check_input(xs, "pool")?;
+        let patches = self.geometry.pool_patches(xs);
+        let pooled = match self.kind {
+            PoolKind::Max => patches.max(0)?,
+            PoolKind::Avg => patches.mean(0)?,
+        };
+        Ok(self.geometry.batched(&pooled, self.geometry.channels, xs.shape().1))
+    }
+
+    fn describe(&self, one_dim: bool) -> String {
+        let g = &self.geometry;
+        let (height, width) = g.out_size();
+        let name = match self.kind {
+            PoolKind::Max => "MaxPool",
+            PoolKind::Avg => "AvgPool",
+        };
+        if one_dim {
+            format!("{}1d({}x{} -> {}x{}, kernel {}, stride {})", name, g.channels, g.width, g.channels, width, g.kernel.1, g.stride.1)
+        } else {
+            format!(
+                "{}2d({}x{}x{} -> {}x{}x{}, kernel {:?}, stride {:?})",
+                name, g.channels, g.height, g.width, g.channels, height, width, g.kernel, g.stride
+            )
+        }
+    }
+}
+
+/// Maximum over windows of every channel of feature maps of shape `(channels, height, width)`
+/// or, when built with `new1d`, of signals of shape `(channels, length)`. When several
+/// elements of a window are maximal, the gradient is divided evenly among them.
+#[derive(Debug, Clone)]
+pub struct MaxPool {
+    pool: Pool,
+    one_dim: bool
+}
+
+impl MaxPool {
+    /// Panics if a dimension of `kernel` or `stride` is 0.
+    pub fn new2d(channels: usize, input: (usize, usize), kernel: (usize, usize), stride: (usize, usize)) -> Self {
+        Self { pool: Pool::new(PoolKind::Max, channels, input, kernel, stride), one_dim: false }
+    }
+
+    /// Panics if `kernel` or `stride` is 0.
+    pub fn new1d(channels: usize, length: usize, kernel: usize, stride: usize) -> Self {
+        Self { pool: Pool::new(PoolKind::Max, channels, (1, length), (1, kernel), (1, stride)), one_dim: true }
+    }
+
+    /// `(channels, height, width)` of the output, the height of 1-D outputs is 1.
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        let (height, width) = self.pool.geometry.out_size();
+        (self.pool.geometry.channels, height, width)
+    }
+}
+
+impl Module for MaxPool {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        self.pool.forward(xs)
+    }
+
+    fn describe(&self) -> String {
+        self.pool.describe(self.one_dim)
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.pool.geometry.features())
+    }
+}
+
+/// Mean over windows of every channel, see `MaxPool`.
+#[derive(Debug, Clone)]
+pub struct AvgPool {
+    pool: Pool,
+    one_dim: bool
+}
+
+impl AvgPool {
+    /// Panics if a dimension of `kernel` or `stride` is 0.
+    pub fn new2d(channels: usize, input: (usize, usize), kernel: (usize, usize), stride: (usize, usize)) -> Self {
+        Self { pool: Pool::new(PoolKind::Avg, channels, input, kernel, stride), one_dim: false }
+    }
+
+    /// Panics if `kernel` or `stride` is 0.
+    pub fn new1d(channels: usize, length: usize, kernel: usize, stride: usize) -> Self {
+        Self { pool: Pool::new(PoolKind::Avg, channels, (1, length), (1, kernel), (1, stride)), one_dim: true }
+    }
+
+    /// `(channels, height, width)` of the output, the height of 1-D outputs is 1.
+    pub fn output_shape(&self) -> (usize, usize, usize) {
+        let (height, width) = self.pool.geometry.out_size();
+        (self.pool.geometry.channels, height, width)
+    }
+}
+
+impl Module for AvgPool {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        self.pool.forward(xs)
+    }
+
+    fn describe(&self) -> String {
+        self.pool.describe(self.one_dim)
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.pool.geometry.features())
+    }
+}
+
+/// Marks the transition from feature maps to flat features. Feature maps are already
+/// stored as columns of `channels * height * width` features, so the input is passed through.
+#[derive(Debug, Clone, Default)]
+pub struct Flatten;
+
+impl Flatten {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for Flatten {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        Ok(xs.clone())
+    }
+
+    fn describe(&self) -> String {
+        "Flatten".to_string()
+    }
+}
@@ -1,4 +1,6 @@
-use std::{error::Error, ops::{IndexMut, Index}};
+use std::error::Error;
+
+use crate::{Float, Matrix, error::UtilityError};
 
 #[derive(Debug, PartialEq)]
 pub enum DFType {
@@ -58,7 +60,66 @@ impl DataFrame {
         Ok(Self { headers, data, shape: (rows, cols) })
     }
 
-    pub fn encode(&mut self, encoding: EncodingScheme) {
+    pub fn headers(&self) -> &Vec<String> {
+        &self.headers
+    }
+
+    pub fn data(&self) -> &Vec<DFType> {
+        &self.data
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Converts the data into a `(rows, cols)` matrix of element type `T`, 
+    /// e.g. `df.to_matrix::<f64>()`. Fails on the first value that is not a number.
+    pub fn to_matrix<T: Float>(&self) -> Result<Matrix<T>, UtilityError> {
+        let (_, cols) = self.shape;
+        let data = self.data
+            .iter()
+            .enumerate()
+            .map(|(k, x)| match x {
+                DFType::F32(x) => Ok(T::from_f64(*x as f64)),
+                DFType::F64(x) => Ok(T::from_f64(*x)),
+                DFType::STR(_) => Err(UtilityError::NonNumericValue { 
+                    row: k / cols, 
+                    column: self.headers[k % cols].clone() 
+                })
+            })
+            .collect::<Result<Vec<T>, UtilityError>>()?;
+        Ok(Matrix::from_vec(data, self.shape, false))
+    }
+
+    /// Replaces the values of `column` by the index of their category, e.g. for an `Embedding`.
+    /// Returns the categories in order of first appearance, such that category `i` is encoded as `i`.
+    pub fn label_encode(&mut self, column: &str) -> Result<Vec<String>, UtilityError> {
+        let (_, cols) = self.shape;
+        let j = self.headers
+            .iter()
+            .position(|header| header == column)
+            .ok_or_else(|| UtilityError::UnknownColumn { column: column.to_string() })?;
+
+        let mut categories: Vec<String> = vec![];
+        for value in self.data.iter_mut().skip(j).step_by(cols) {
+            let category = match value {
+                DFType::F32(x) => x.to_string(),
+                DFType::F64(x) => x.to_string(),
+                DFType::STR(x) => x.clone()
+            };
+            let index = categories
+                .iter()
+                .position(|c| *c == category)
+                .unwrap_or_else(|| {
+                    categories.push(category);
+                    categories.len() - 1
+                });
+            *value = DFType::F32(index as f32);
+        }
+        Ok(categories)
+    }
+
+    pub fn encode(&mut self, _encoding: EncodingScheme) {
         
     }
 
@@ -74,7 +135,7 @@ mod tests {
         let file_path = "examples/iris/test_input.csv";
         let df = DataFrame::from_csv(file_path);
 
-        assert!(!df.is_err());
+        assert!(df.is_ok());
         let df = df.unwrap();
         assert_eq!(df.headers, vec!["id", "test", "label"]);
         assert_eq!(df.shape, (3, 3));
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::{Matrix, Module, Parameter, error::MatrixError};
+
+/// Lookup table mapping category indices to trainable vectors, with a `weight`
+/// of shape `(num_embeddings, dim)` whose row `i` is the vector of category `i`.
+/// The gradient of `weight` is row-sparse, and training only updates the rows of the
+/// categories in the batch.
+///
+/// Every row of the input holds the indices of one categorical feature, one per sample,
+/// stored as floats. An input of shape `(k, samples)` gives an output of shape
+/// `(k * dim, samples)`, the vectors of the features stacked along the rows.
+#[derive(Debug, Clone)]
+pub struct Embedding {
+    weight: Parameter
+}
+
+impl Embedding {
+    pub fn new(num_embeddings: usize, dim: usize) -> Self {
+        Self { weight: Parameter::new("weight", Matrix::randn(-1., 1., (num_embeddings, dim), true)) }
+    }
+
+    pub fn weight(&self) -> &Parameter {
+        &self.weight
+    }
+
+    pub fn num_embeddings(&self) -> usize {
+        self.weight.value().shape().0
+    }
+
+    pub fn dim(&self) -> usize {
+        self.weight.value().shape().1
+    }
+}
+
+impl Module for Embedding {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let (features, samples) = xs.shape();
+        let dim = self.dim();
+
+        let indices = xs
+            .data()
+            .iter()
+            .map(|&x| {
+                if x < 0. || x.fract() != 0. || x as usize >= self.num_embeddings() {
+                    return Err(MatrixError::InvalidIndex { value: x as f64, len: self.num_embeddings(), op: "embedding".to_string() });
+                }
+                Ok(x as usize)
+            })
+            .collect::<Result<Vec<usize>, MatrixError>>()?;
+
+        // element (feature * dim + d, n) is element d of the row looked up by (feature, n)
+        let mut map = Vec::with_capacity(features * dim * samples);
+        for feature in 0..features {
+            for d in 0..dim {
+                for n in 0..samples {
+                    map.push(indices[feature * samples + n] * dim + d);
+                }
+            }
+        }
+        Ok(self.weight.value().lookup_map(Arc::new(map), (features * dim, samples)))
+    }
+
+    fn describe(&self) -> String {
+        format!("Embedding({}, {})", self.num_embeddings(), self.dim())
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        vec![&self.weight]
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weight]
+    }
+}
+
+/// Input layer for tabular data mixing categorical and numeric features: the rows
+/// (features) of the input with an embedding are replaced by their embedded vectors,
+/// the others are passed through, keeping the order of the features.
+///
+/// The parameters of the embedding of feature `i` are prefixed with `i`, e.g. `2.weight`.
+#[derive(Debug, Clone)]
+pub struct FeatureEmbedding {
+    features: usize,
+    embeddings: Vec<(usize, Embedding)>
+}
+
+impl FeatureEmbedding {
+    pub fn new(features: usize) -> Self {
+        Self { features, embeddings: vec![] }
+    }
+
+    /// Embeds feature `feature`, whose values are category indices.
+    /// Panics if the feature does not exist or already has an embedding.
+    pub fn with_embedding(mut self, feature: usize, mut embedding: Embedding) -> Self {
+        assert!(feature < self.features, "feature {} out of bounds for {} features", feature, self.features);
+        assert!(self.embedding(feature).is_none(), "feature {} already has an embedding", feature);
+
+        for param in embedding.parameters_mut() {
+            param.add_prefix(&feature.to_string());
+        }
+        self.embeddings.push((feature, embedding));
+        self.embeddings.sort_by_key(|(feature, _)| *feature);
+        self
+    }
+
+    pub fn embedding(&self, feature: usize) -> Option<&Embedding> {
+        self.embeddings
+            .iter()
+            .find(|(f, _)| *f == feature)
+            .map(|(_, embedding)| embedding)
+    }
+
+    /// Number of rows of the output.
+    pub fn outputs(&self) -> usize {
+        (0..self.features)
+            .map(|feature| self.embedding(feature).map_or(1, Embedding::dim))
+            .sum()
+    }
+}
+
+impl Module for FeatureEmbedding {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        if xs.shape().0 != self.features {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: (self.features, xs.shape().1),
+                b_shape: xs.shape(),
+                op: "feature embedding".to_string()
+            });
+        }
+
+        let parts = (0..self.features)
+            .map(|feature| {
+                let row = xs.slice_rows(feature, feature + 1)?;
+                match self.embedding(feature) {
+                    Some(embedding) => embedding.forward(&row),
+                    None => Ok(row)
+                }
+            })
+            .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+
+        Matrix::concat(&parts.iter().collect::<Vec<&Matrix>>(), 0)
+    }
+
+    fn describe(&self) -> String {
+        format!("FeatureEmbedding({} -> {})", self.features, self.outputs())
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.features)
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        self.embeddings
+            .iter()
+            .flat_map(|(_, embedding)| embedding.parameters())
+            .collect()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        self.embeddings
+            .iter_mut()
+            .flat_map(|(_, embedding)| embedding.parameters_mut())
+            .collect()
+    }
+
+    fn children(&self) -> Vec<&dyn Module> {
+        self.embeddings
+            .iter()
+            .map(|(_, embedding)| embedding as &dyn Module)
+            .collect()
+    }
+}
@@ -1,6 +1,8 @@
 use std::{error::Error, fmt::Display};
 
-#[derive(Debug)]
+use crate::anomaly::Pass;
+
+#[derive(Debug, Clone)]
 pub enum MatrixError {
     ShapeMismatchError {
         a_shape: (usize, usize),
@@ -10,6 +12,43 @@ pub enum MatrixError {
     BroadCastError {
         got_shape: (usize, usize),
         expected_shape: (usize, usize),
+    },
+    InvalidAxis {
+        axis: usize,
+        op: String,
+    },
+    IndexOutOfBounds {
+        index: usize,
+        len: usize,
+        op: String,
+    },
+    InvalidIndex {
+        value: f64,
+        len: usize,
+        op: String,
+    },
+    InvalidRange {
+        start: usize,
+        end: usize,
+        op: String,
+    },
+    NotSquare {
+        shape: (usize, usize),
+        op: String,
+    },
+    SingularMatrix {
+        op: String,
+    },
+    NotPositiveDefinite {
+        op: String,
+    },
+    AnomalyError {
+        op: String,
+        node_id: usize,
+        pass: Pass,
+        input_shapes: Vec<(usize, usize)>,
+        output_shape: (usize, usize),
+        op_chain: Vec<String>,
     }
 }
 
@@ -25,7 +64,44 @@ impl Display for MatrixError {
             MatrixError::BroadCastError { got_shape, expected_shape } =>
                 writeln!(f, "Broadcast error: could not broadcast shape {:?} into shape {:?}",
                     got_shape, expected_shape    
+                ),
+            MatrixError::InvalidAxis { axis, op } =>
+                writeln!(f, "Invalid axis error during [{}] operation: axis {} is not 0 (rows) or 1 (columns)",
+                    op, axis
+                ),
+            MatrixError::IndexOutOfBounds { index, len, op } =>
+                writeln!(f, "Index out of bounds error during [{}] operation: index {} is out of bounds for length {}",
+                    op, index, len
+                ),
+            MatrixError::InvalidIndex { value, len, op } =>
+                writeln!(f, "Invalid index error during [{}] operation: value {} is not an integer index in 0..{}",
+                    op, value, len
+                ),
+            MatrixError::InvalidRange { start, end, op } =>
+                writeln!(f, "Invalid range error during [{}] operation: start {} is greater than end {}",
+                    op, start, end
+                ),
+            MatrixError::NotSquare { shape, op } =>
+                writeln!(f, "Not square error during [{}] operation: expected a square matrix, got shape {:?}",
+                    op, shape
+                ),
+            MatrixError::SingularMatrix { op } =>
+                writeln!(f, "Singular matrix error during [{}] operation: the matrix is singular or rank deficient",
+                    op
+                ),
+            MatrixError::NotPositiveDefinite { op } =>
+                writeln!(f, "Not positive definite error during [{}] operation: the matrix is not symmetric positive definite",
+                    op
+                ),
+            MatrixError::AnomalyError { op, node_id, pass, input_shapes, output_shape, op_chain } => {
+                let location = match pass {
+                    Pass::Forward => "output",
+                    Pass::Backward => "input gradients"
+                };
+                writeln!(f, "Anomaly error: non-finite value in {} of [{}] operation (node {}) with input shapes {:?} and output shape {:?}, op chain: {}",
+                    location, op, node_id, input_shapes, output_shape, op_chain.join(" <- ")
                 )
+            }
         }
     }
 }
@@ -39,6 +115,34 @@ pub enum NNError {
     BatchSizeExceedsTrainingSet {
         batch_size: usize,
         train_size: usize,
+    },
+    MissingParameter {
+        name: String
+    },
+    ParameterShapeMismatch {
+        name: String,
+        expected: (usize, usize),
+        got: (usize, usize)
+    },
+    InvalidStateFile {
+        line: usize
+    },
+    FeatureCountMismatch {
+        expected: usize,
+        got: usize
+    },
+    RaggedRows {
+        row: usize,
+        expected: usize,
+        got: usize
+    },
+    NegativeOutput {
+        sample: usize,
+        value: f32
+    },
+    ProbabilityOutOfRange {
+        sample: usize,
+        value: f32
     }
 }
 
@@ -54,7 +158,31 @@ impl Display for NNError {
             NNError::BatchSizeExceedsTrainingSet { batch_size, train_size } =>
                 writeln!(f, "Batch size mismatch error: batch size must be less than training data records, batch size is {} and training data size is {}",
                     batch_size, train_size    
-                )
+                ),
+            NNError::MissingParameter { name } =>
+                writeln!(f, "Missing parameter error: no value for parameter {} in the state", name),
+            NNError::ParameterShapeMismatch { name, expected, got } =>
+                writeln!(f, "Parameter shape mismatch error: parameter {} has shape {:?}, but the state holds shape {:?}",
+                    name, expected, got
+                ),
+            NNError::InvalidStateFile { line } =>
+                writeln!(f, "Invalid state file error: line {} is not of the form `name rows cols values...`", line),
+            NNError::FeatureCountMismatch { expected, got } =>
+                writeln!(f, "Feature count mismatch error: the model expects {} features per sample, but the input has {}",
+                    expected, got
+                ),
+            NNError::RaggedRows { row, expected, got } =>
+                writeln!(f, "Ragged rows error: row {} has {} values, whereas the first row has {}",
+                    row, got, expected
+                ),
+            NNError::NegativeOutput { sample, value } =>
+                writeln!(f, "Negative output error: sample {} has the output {}, which is not a probability or a weight",
+                    sample, value
+                ),
+            NNError::ProbabilityOutOfRange { sample, value } =>
+                writeln!(f, "Probability out of range error: the single output of sample {} is {}, which is not in [0, 1]",
+                    sample, value
+                ),
         }
     }
 }
@@ -65,6 +193,13 @@ pub enum UtilityError {
         y_index: usize,
         csv_column_len: usize
     },
+    NonNumericValue {
+        row: usize,
+        column: String
+    },
+    UnknownColumn {
+        column: String
+    },
 }
 
 impl Error for UtilityError {}
@@ -76,6 +211,12 @@ impl Display for UtilityError {
                 writeln!(f, "Index out of bounds error: the y_index {} is out of bounds for the CSV column length {}",
                     y_index, csv_column_len    
                 ),
+            UtilityError::NonNumericValue { row, column } => 
+                writeln!(f, "Non-numeric value error: row {} of column {} is not a number",
+                    row, column
+                ),
+            UtilityError::UnknownColumn { column } =>
+                writeln!(f, "Unknown column error: there is no column named {}", column),
         }
     }
 }
\ No newline at end of file
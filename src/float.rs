@@ -0,0 +1,75 @@
+use std::{
+    fmt::{Debug, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub}
+};
+
+use rand::distributions::uniform::SampleUniform;
+
+/// Element type of a `Matrix`, implemented for `f32` and `f64`.
+pub trait Float:
+    Copy + Debug + Display + Default + PartialEq + PartialOrd + SampleUniform + Sum + Send + Sync + 'static +
+    Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> +
+    Neg<Output = Self> + AddAssign
+{
+    const NAN: Self;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn from_usize(x: usize) -> Self {
+        Self::from_f64(x as f64)
+    }
+
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn ln_1p(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tanh(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn signum(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn clamp(self, min: Self, max: Self) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+    fn is_infinite(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($t: ty) => {
+        impl Float for $t {
+            const NAN: Self = <$t>::NAN;
+
+            fn zero() -> Self { 0. }
+            fn one() -> Self { 1. }
+            fn from_f64(x: f64) -> Self { x as $t }
+            fn to_f64(self) -> f64 { self as f64 }
+
+            fn exp(self) -> Self { <$t>::exp(self) }
+            fn ln(self) -> Self { <$t>::ln(self) }
+            fn ln_1p(self) -> Self { <$t>::ln_1p(self) }
+            fn sqrt(self) -> Self { <$t>::sqrt(self) }
+            fn abs(self) -> Self { <$t>::abs(self) }
+            fn sin(self) -> Self { <$t>::sin(self) }
+            fn cos(self) -> Self { <$t>::cos(self) }
+            fn tanh(self) -> Self { <$t>::tanh(self) }
+            fn powf(self, n: Self) -> Self { <$t>::powf(self, n) }
+            fn signum(self) -> Self { <$t>::signum(self) }
+            fn max(self, other: Self) -> Self { <$t>::max(self, other) }
+            fn min(self, other: Self) -> Self { <$t>::min(self, other) }
+            fn clamp(self, min: Self, max: Self) -> Self { <$t>::clamp(self, min, max) }
+            fn is_finite(self) -> bool { <$t>::is_finite(self) }
+            fn is_nan(self) -> bool { <$t>::is_nan(self) }
+            fn is_infinite(self) -> bool { <$t>::is_infinite(self) }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
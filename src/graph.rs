@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use crate::{Float, GradMap, Matrix};
+
+// collects every node of the graph, leaves included, with inputs before outputs
+fn collect<'a, T: Float>(
+    node: &'a Matrix<T>,
+    nodes: &mut Vec<&'a Matrix<T>>,
+    already_seen: &mut HashSet<usize>
+) {
+    if !already_seen.insert(node.id()) {
+        return;
+    }
+
+    if let Some(op) = node.op() {
+        for mat in op.operands() {
+            collect(mat, nodes, already_seen);
+        }
+    }
+    nodes.push(node);
+}
+
+fn op_name<T: Float>(node: &Matrix<T>) -> String {
+    match node.op() {
+        Some(op) => op.to_string(),
+        None => "Leaf".to_string()
+    }
+}
+
+fn grad_norm<T: Float>(node: &Matrix<T>, grads: Option<&GradMap<T>>) -> Option<T> {
+    grads.and_then(|grads| grads.norm(node))
+}
+
+fn escape_json(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl<T: Float> Matrix<T> {
+
+    /// Returns every node of the computation graph rooted at this matrix exactly once,
+    /// ordered such that the inputs of an operation precede the operation itself.
+    pub fn graph_nodes(&self) -> Vec<&Matrix<T>> {
+        let mut nodes = vec![];
+        collect(self, &mut nodes, &mut HashSet::new());
+        nodes
+    }
+
+    /// Exports the computation graph rooted at this matrix in the Graphviz DOT format.
+    /// Shared subgraphs are emitted once. When `grads` is given, the L2 norm of
+    /// each node's gradient is added to its label.
+    pub fn to_dot(&self, grads: Option<&GradMap<T>>) -> String {
+        let nodes = self.graph_nodes();
+
+        let mut dot = String::from("digraph {\n");
+        for node in nodes.iter() {
+            let mut label = format!("{} #{}\\nshape: {:?}", op_name(node), node.id(), node.shape());
+            if let Some(norm) = grad_norm(node, grads) {
+                label.push_str(&format!("\\ngrad norm: {}", norm));
+            }
+            let style = if node.requires_grad() { "solid" } else { "dashed" };
+            dot.push_str(&format!("    n{} [label=\"{}\", shape=box, style={}];\n", node.id(), label, style));
+        }
+        for node in nodes.iter() {
+            if let Some(op) = node.op() {
+                for mat in op.operands() {
+                    dot.push_str(&format!("    n{} -> n{};\n", mat.id(), node.id()));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the computation graph rooted at this matrix as JSON with a `nodes` list
+    /// (id, op, shape, requires_grad and grad_norm) and an `edges` list from input to output.
+    /// `op` is `null` for leaves and `grad_norm` is `null` when no gradient is known.
+    pub fn to_json(&self, grads: Option<&GradMap<T>>) -> String {
+        let nodes = self.graph_nodes();
+
+        let node_entries = nodes
+            .iter()
+            .map(|node| {
+                let op = match node.op() {
+                    Some(op) => format!("\"{}\"", escape_json(&op.to_string())),
+                    None => "null".to_string()
+                };
+                let norm = match grad_norm(node, grads) {
+                    Some(norm) if norm.is_finite() => norm.to_string(),
+                    _ => "null".to_string()
+                };
+                format!(
+                    "{{\"id\":{},\"op\":{},\"shape\":[{},{}],\"requires_grad\":{},\"grad_norm\":{}}}",
+                    node.id(), op, node.shape().0, node.shape().1, node.requires_grad(), norm
+                )
+            })
+            .collect::<Vec<String>>();
+
+        let edge_entries = nodes
+            .iter()
+            .filter_map(|node| node.op().as_ref().map(|op| (node, op)))
+            .flat_map(|(node, op)| op
+                .operands()
+                .into_iter()
+                .map(move |mat| format!("{{\"from\":{},\"to\":{}}}", mat.id(), node.id()))
+            )
+            .collect::<Vec<String>>();
+
+        format!("{{\"nodes\":[{}],\"edges\":[{}]}}", node_entries.join(","), edge_entries.join(","))
+    }
+}
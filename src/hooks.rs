@@ -0,0 +1,85 @@
+use std::{fmt::Debug, sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}}};
+
+use crate::{Float, Matrix, error::MatrixError};
+
+type Hook<T> = Arc<dyn Fn(&Matrix<T>) -> Option<Matrix<T>> + Send + Sync>;
+
+/// Identifies a hook registered with `Matrix::register_hook`. Handles are unique
+/// across all matrices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookHandle(usize);
+
+// unique id of a hook
+fn next_hook_id() -> usize {
+    static NEXT_HOOK_ID: AtomicUsize = AtomicUsize::new(1);
+    NEXT_HOOK_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+pub(crate) struct Hooks<T> {
+    hooks: Mutex<Vec<(usize, Hook<T>)>>
+}
+
+impl<T> Default for Hooks<T> {
+    fn default() -> Self {
+        Self { hooks: Mutex::new(vec![]) }
+    }
+}
+
+impl<T> Debug for Hooks<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Hooks({})", self.hooks.lock().unwrap().len())
+    }
+}
+
+impl<T> Hooks<T> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hooks.lock().unwrap().is_empty()
+    }
+}
+
+impl<T: Float> Matrix<T> {
+
+    /// Registers a hook that is called with the gradient of this matrix during `backward`,
+    /// once all contributions to the gradient have been accumulated. When the hook returns
+    /// a matrix, it replaces the gradient and is what gets propagated further.
+    /// Hooks are called in the order in which they were registered and are shared by all 
+    /// clones of this matrix.
+    pub fn register_hook<F>(&self, hook: F) -> HookHandle 
+    where F: Fn(&Matrix<T>) -> Option<Matrix<T>> + Send + Sync + 'static
+    {
+        let id = next_hook_id();
+        self.hooks().hooks.lock().unwrap().push((id, Arc::new(hook)));
+        HookHandle(id)
+    }
+
+    /// Removes a hook, returns `false` if it was not registered on this matrix.
+    pub fn remove_hook(&self, handle: HookHandle) -> bool {
+        let mut hooks = self.hooks().hooks.lock().unwrap();
+        let len = hooks.len();
+        hooks.retain(|(id, _)| *id != handle.0);
+        hooks.len() != len
+    }
+
+    pub(crate) fn apply_hooks(&self, grad: Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        if self.hooks().is_empty() {
+            return Ok(grad);
+        }
+
+        // clone the hooks, such that a hook can register or remove hooks itself
+        let hooks = self.hooks().hooks.lock().unwrap().clone();
+        let mut grad = grad;
+        for (_, hook) in hooks.iter() {
+            if let Some(new_grad) = hook(&grad) {
+                if new_grad.shape() != grad.shape() {
+                    return Err(MatrixError::ShapeMismatchError { 
+                        a_shape: grad.shape(), 
+                        b_shape: new_grad.shape(), 
+                        op: "hook".to_string() 
+                    });
+                }
+                grad = new_grad;
+            }
+        }
+        Ok(grad)
+    }
+}
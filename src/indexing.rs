@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use crate::{Float, Matrix, Operator, UnaryOpType, anomaly, error::MatrixError};
+
+type MatrixResult<T> = Result<Matrix<T>, MatrixError>;
+
+fn check_index(index: usize, len: usize, op: &str) -> Result<(), MatrixError> {
+    if index >= len {
+        return Err(MatrixError::IndexOutOfBounds { index, len, op: op.to_string() });
+    }
+    Ok(())
+}
+
+fn check_range(start: usize, end: usize, len: usize, op: &str) -> Result<(), MatrixError> {
+    if start > end {
+        return Err(MatrixError::InvalidRange { start, end, op: op.to_string() });
+    }
+    if end > len {
+        return Err(MatrixError::IndexOutOfBounds { index: end, len, op: op.to_string() });
+    }
+    Ok(())
+}
+
+impl<T: Float> Matrix<T> {
+
+    /// Builds a matrix of `shape` whose element `k` is element `map[k]` of this matrix.
+    /// All indices in `map` must be valid.
+    pub(crate) fn index_map(&self, map: Arc<Vec<usize>>, shape: (usize, usize)) -> Matrix<T> {
+        self.gather_elements(map.clone(), shape, UnaryOpType::Index(map))
+    }
+
+    /// Same as `index_map`, for looking up rows of a table such as an embedding. When this
+    /// matrix is a leaf, its gradient only holds the rows that were looked up, see `RowGrad`.
+    pub(crate) fn lookup_map(&self, map: Arc<Vec<usize>>, shape: (usize, usize)) -> Matrix<T> {
+        self.gather_elements(map.clone(), shape, UnaryOpType::Lookup(map))
+    }
+
+    fn gather_elements(&self, map: Arc<Vec<usize>>, shape: (usize, usize), op_type: UnaryOpType<T>) -> Matrix<T> {
+        let data = map
+            .iter()
+            .map(|&k| self.data()[k])
+            .collect();
+
+        let op = Operator::Unary(self.clone(), op_type);
+
+        Matrix::from_op(data, shape, op, self.requires_grad())
+    }
+
+    /// Builds a matrix of `shape` by adding element `k` of this matrix to element `map[k]`.
+    /// All indices in `map` must be valid.
+    pub(crate) fn scatter_map(&self, map: Arc<Vec<usize>>, shape: (usize, usize)) -> Matrix<T> {
+        let mut data = vec![T::zero(); shape.0 * shape.1];
+        for (&k, &x) in map.iter().zip(self.data().iter()) {
+            data[k] += x;
+        }
+
+        let op = Operator::Unary(self.clone(), UnaryOpType::Scatter(map));
+
+        Matrix::from_op(data, shape, op, self.requires_grad())
+    }
+
+    fn indexed(&self, map: Vec<usize>, shape: (usize, usize)) -> MatrixResult<T> {
+        let mat = self.index_map(Arc::new(map), shape);
+        anomaly::raise(&mat)?;
+        Ok(mat)
+    }
+
+    /// Rows `start..end` of this matrix.
+    pub fn slice_rows(&self, start: usize, end: usize) -> MatrixResult<T> {
+        let (rows, cols) = self.shape();
+        check_range(start, end, rows, "slice_rows")?;
+
+        self.indexed((start * cols..end * cols).collect(), (end - start, cols))
+    }
+
+    /// Columns `start..end` of this matrix.
+    pub fn slice_cols(&self, start: usize, end: usize) -> MatrixResult<T> {
+        let (rows, cols) = self.shape();
+        check_range(start, end, cols, "slice_cols")?;
+
+        let map = (0..rows)
+            .flat_map(|i| (start..end).map(move |j| i * cols + j))
+            .collect();
+        self.indexed(map, (rows, end - start))
+    }
+
+    /// Selects the rows (axis 0) or columns (axis 1) at `indices`, in that order.
+    /// Indices may repeat.
+    pub fn select(&self, axis: usize, indices: &[usize]) -> MatrixResult<T> {
+        let (rows, cols) = self.shape();
+        match axis {
+            0 => {
+                for &i in indices.iter() {
+                    check_index(i, rows, "select")?;
+                }
+                let map = indices
+                    .iter()
+                    .flat_map(|&i| (0..cols).map(move |j| i * cols + j))
+                    .collect();
+                self.indexed(map, (indices.len(), cols))
+            },
+            1 => {
+                for &j in indices.iter() {
+                    check_index(j, cols, "select")?;
+                }
+                let map = (0..rows)
+                    .flat_map(|i| indices.iter().map(move |&j| i * cols + j))
+                    .collect();
+                self.indexed(map, (rows, indices.len()))
+            },
+            _ => Err(MatrixError::InvalidAxis { axis, op: "select".to_string() })
+        }
+    }
+
+    // flat positions addressed by gather and scatter_add
+    fn gather_map(&self, axis: usize, indices: &[usize], op: &str) -> Result<(Vec<usize>, (usize, usize)), MatrixError> {
+        let (rows, cols) = self.shape();
+        let (lanes, lane_len) = match axis {
+            0 => (cols, rows),
+            1 => (rows, cols),
+            _ => return Err(MatrixError::InvalidAxis { axis, op: op.to_string() })
+        };
+        if indices.len() != lanes {
+            return Err(MatrixError::ShapeMismatchError { 
+                a_shape: self.shape(), 
+                b_shape: if axis == 0 { (1, indices.len()) } else { (indices.len(), 1) }, 
+                op: op.to_string() 
+            });
+        }
+        let mut map = Vec::with_capacity(lanes);
+        for (lane, &index) in indices.iter().enumerate() {
+            check_index(index, lane_len, op)?;
+            map.push(if axis == 0 { index * cols + lane } else { lane * cols + index });
+        }
+        let shape = if axis == 0 { (1, cols) } else { (rows, 1) };
+        Ok((map, shape))
+    }
+
+    /// Picks one element per lane: for axis 1, element `indices[i]` of every row `i`,
+    /// resulting in a `(rows, 1)` matrix; for axis 0, element `indices[j]` of every column `j`,
+    /// resulting in a `(1, cols)` matrix.
+    pub fn gather(&self, axis: usize, indices: &[usize]) -> MatrixResult<T> {
+        let (map, shape) = self.gather_map(axis, indices, "gather")?;
+        self.indexed(map, shape)
+    }
+
+    /// The inverse of `gather`: adds the elements of `src` to this matrix at the positions
+    /// `gather` would read them from. `src` must have the shape `gather` returns.
+    pub fn scatter_add(&self, axis: usize, indices: &[usize], src: &Matrix<T>) -> MatrixResult<T> {
+        let (map, shape) = self.gather_map(axis, indices, "scatter_add")?;
+        if src.shape() != shape {
+            return Err(MatrixError::ShapeMismatchError { 
+                a_shape: shape, 
+                b_shape: src.shape(), 
+                op: "scatter_add".to_string() 
+            });
+        }
+        let scattered = src.scatter_map(Arc::new(map), self.shape());
+        anomaly::raise(&scattered)?;
+        self.add(&scattered)
+    }
+
+    /// Concatenates `mats` along the rows (axis 0) or columns (axis 1).
+    pub fn concat(mats: &[&Matrix<T>], axis: usize) -> MatrixResult<T> {
+        let Some(first) = mats.first() else {
+            return Ok(Matrix::from_vec(vec![], (0, 0), false));
+        };
+        if axis > 1 {
+            return Err(MatrixError::InvalidAxis { axis, op: "concat".to_string() });
+        }
+
+        for mat in mats.iter() {
+            let matches = if axis == 0 { mat.shape().1 == first.shape().1 } else { mat.shape().0 == first.shape().0 };
+            if !matches {
+                return Err(MatrixError::ShapeMismatchError { 
+                    a_shape: first.shape(), 
+                    b_shape: mat.shape(), 
+                    op: "concat".to_string() 
+                });
+            }
+        }
+
+        let (shape, data) = if axis == 0 {
+            let rows = mats.iter().map(|mat| mat.shape().0).sum();
+            let data = mats.iter().flat_map(|mat| mat.data().iter().copied()).collect();
+            ((rows, first.shape().1), data)
+        } else {
+            let (rows, cols) = (first.shape().0, mats.iter().map(|mat| mat.shape().1).sum());
+            let mut data = Vec::with_capacity(rows * cols);
+            for i in 0..rows {
+                for mat in mats.iter() {
+                    let mat_cols = mat.shape().1;
+                    data.extend_from_slice(&mat.data()[i * mat_cols..(i + 1) * mat_cols]);
+                }
+            }
+            ((rows, cols), data)
+        };
+
+        let req_grad = mats.iter().any(|mat| mat.requires_grad());
+        let op = Operator::Concat(mats.iter().map(|&mat| mat.clone()).collect(), axis);
+
+        let mat = Matrix::from_op(data, shape, op, req_grad);
+        anomaly::raise(&mat)?;
+        Ok(mat)
+    }
+
+    /// Stacks matrices of equal shape by flattening each of them into a row (axis 0) 
+    /// or a column (axis 1), e.g., to collect single samples into a batch.
+    pub fn stack(mats: &[&Matrix<T>], axis: usize) -> MatrixResult<T> {
+        let Some(first) = mats.first() else {
+            return Ok(Matrix::from_vec(vec![], (0, 0), false));
+        };
+        let len = first.data().len();
+        let flat = mats
+            .iter()
+            .map(|mat| {
+                if mat.shape() != first.shape() {
+                    return Err(MatrixError::ShapeMismatchError { 
+                        a_shape: first.shape(), 
+                        b_shape: mat.shape(), 
+                        op: "stack".to_string() 
+                    });
+                }
+                match axis {
+                    0 => mat.reshape((1, len)),
+                    1 => mat.reshape((len, 1)),
+                    _ => Err(MatrixError::InvalidAxis { axis, op: "stack".to_string() })
+                }
+            })
+            .collect::<Result<Vec<Matrix<T>>, MatrixError>>()?;
+
+        Matrix::concat(&flat.iter().collect::<Vec<&Matrix<T>>>(), axis)
+    }
+
+    /// Splits this matrix along the rows (axis 0) or columns (axis 1) into parts of the given `sizes`,
+    /// which must add up to the size of the axis.
+    pub fn split(&self, axis: usize, sizes: &[usize]) -> Result<Vec<Matrix<T>>, MatrixError> {
+        let (rows, cols) = self.shape();
+        let len = match axis {
+            0 => rows,
+            1 => cols,
+            _ => return Err(MatrixError::InvalidAxis { axis, op: "split".to_string() })
+        };
+        let total = sizes.iter().sum::<usize>();
+        if total != len {
+            return Err(MatrixError::ShapeMismatchError { 
+                a_shape: self.shape(), 
+                b_shape: if axis == 0 { (total, cols) } else { (rows, total) }, 
+                op: "split".to_string() 
+            });
+        }
+
+        let mut offset = 0;
+        sizes
+            .iter()
+            .map(|&size| {
+                let part = match axis {
+                    0 => self.slice_rows(offset, offset + size),
+                    _ => self.slice_cols(offset, offset + size),
+                };
+                offset += size;
+                part
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,135 @@
+use rand::Rng;
+
+use crate::{Matrix, Module, Parameter, error::MatrixError};
+
+/// Elementwise activation function, usable on its own as a layer.
+#[derive(Debug, Clone, Copy)]
+pub enum Activation {
+    Sigmoid,
+    Tanh,
+    Relu,
+    None,
+}
+
+impl Activation {
+    pub fn apply(&self, mat: Matrix) -> Matrix {
+        match self {
+            Self::Sigmoid => mat.sigmoid(),
+            Self::Tanh => mat.tanh(),
+            Self::Relu => mat.clamp(0., f32::INFINITY),
+            Self::None => mat
+        }
+    }
+}
+
+impl Module for Activation {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        Ok(self.apply(xs.clone()))
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Fully-connected layer computing `act(weight @ x + bias)` with parameters
+/// `weight` of shape `(outputs, inputs)` and `bias` of shape `(outputs, 1)`.
+#[derive(Debug, Clone)]
+pub struct Dense {
+    weight: Parameter,
+    bias: Parameter,
+    activation: Activation
+}
+
+impl Dense {
+    pub fn new(inputs: usize, outputs: usize, activation: Activation) -> Self {
+        Self {
+            weight: Parameter::new("weight", Matrix::randn(0., 1., (outputs, inputs), true)),
+            bias: Parameter::new("bias", Matrix::randn(0., 1., (outputs, 1), true)),
+            activation
+        }
+    }
+
+    pub fn weight(&self) -> &Parameter {
+        &self.weight
+    }
+
+    pub fn bias(&self) -> &Parameter {
+        &self.bias
+    }
+}
+
+impl Module for Dense {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        Ok(self.activation.apply(self.weight.value().matmul(xs)?.add(self.bias.value())?))
+    }
+
+    fn describe(&self) -> String {
+        let (outputs, inputs) = self.weight.value().shape();
+        format!("Dense({} -> {}, {:?})", inputs, outputs, self.activation)
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.weight.value().shape().1)
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        vec![&self.weight, &self.bias]
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weight, &mut self.bias]
+    }
+}
+
+/// Inverted dropout: in training mode, every element is zeroed with probability `p`
+/// and the remaining elements are scaled by `1 / (1 - p)`, such that the expected
+/// output equals the input. In evaluation mode the input is passed through unchanged.
+///
+/// A new dropout layer is in training mode. While training, it is stochastic and thus
+/// excluded from checkpointing, so the backward pass uses the mask of the forward pass.
+#[derive(Debug, Clone)]
+pub struct Dropout {
+    p: f32,
+    training: bool
+}
+
+impl Dropout {
+    /// Panics unless `0 <= p < 1`.
+    pub fn new(p: f32) -> Self {
+        assert!((0. ..1.).contains(&p), "dropout probability must be in [0, 1), got {}", p);
+        Self { p, training: true }
+    }
+
+    pub fn p(&self) -> f32 {
+        self.p
+    }
+}
+
+impl Module for Dropout {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        if !self.training || self.p == 0. {
+            return Ok(xs.clone());
+        }
+
+        // the mask is a constant of the graph, so the gradient is masked and scaled alike
+        let scale = 1. / (1. - self.p);
+        let mut rng = rand::thread_rng();
+        let mask = (0..xs.data().len())
+            .map(|_| if rng.gen::<f32>() < self.p { 0. } else { scale })
+            .collect();
+        xs.mul(&Matrix::from_vec(mask, xs.shape(), false))
+    }
+
+    fn describe(&self) -> String {
+        format!("Dropout(p = {})", self.p)
+    }
+
+    fn is_stochastic(&self) -> bool {
+        self.training && self.p > 0.
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
@@ -3,9 +3,46 @@ mod matrix;
 mod error;
 mod neural_network;
 mod dataframe;
+mod graph;
+mod anomaly;
+mod checkpoint;
+mod hooks;
+mod parameter;
+mod reduction;
+mod indexing;
+mod linalg;
+mod sparse;
+mod row_grad;
+mod float;
+mod module;
+mod layers;
+mod normalization;
+mod conv;
+mod recurrent;
+mod embedding;
+mod attention;
+mod combinators;
+mod regularization;
 
 pub use matrix::*;
 pub use autodiff::*;
 pub use crate::neural_network::*;
 pub use error::*;
-pub use dataframe::*;
\ No newline at end of file
+pub use dataframe::*;
+pub use checkpoint::CheckpointFn;
+pub use hooks::HookHandle;
+pub use parameter::*;
+pub use linalg::{Lu, Qr, Eigh, Svd};
+pub use sparse::SparseMatrix;
+pub use row_grad::RowGrad;
+pub use float::Float;
+pub use module::*;
+pub use layers::*;
+pub use normalization::{BatchNorm, LayerNorm};
+pub use conv::{Conv1d, Conv2d, MaxPool, AvgPool, Flatten};
+pub use recurrent::{Rnn, Lstm, Gru, RecurrentOutput};
+pub use embedding::{Embedding, FeatureEmbedding};
+pub use attention::{MultiHeadAttention, PositionalEncoding, TransformerEncoderBlock, scaled_dot_product_attention, causal_mask, positional_encoding};
+pub use combinators::{Residual, Parallel, Merge};
+pub use regularization::{Penalty, Regularizer};
+pub use anomaly::{set_anomaly_detection, is_anomaly_detection_enabled, Pass};
\ No newline at end of file
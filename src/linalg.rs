@@ -0,0 +1,458 @@
+use crate::{Float, Matrix, error::MatrixError};
+
+type MatrixResult<T> = Result<Matrix<T>, MatrixError>;
+
+// pivots and norms below this value are treated as zero
+const EPS: f64 = 1e-10;
+const MAX_SWEEPS: usize = 100;
+
+/// LU decomposition with partial pivoting, `P @ A = L @ U`, where row `i` of `P @ A`
+/// is row `perm[i]` of `A`.
+#[derive(Debug, Clone)]
+pub struct Lu<T = f32> {
+    pub l: Matrix<T>,
+    pub u: Matrix<T>,
+    pub perm: Vec<usize>,
+    sign: f64,
+    singular: bool
+}
+
+/// QR decomposition `A = Q @ R` with orthogonal `Q` of shape `(m, m)` and
+/// upper triangular `R` of shape `(m, n)`.
+#[derive(Debug, Clone)]
+pub struct Qr<T = f32> {
+    pub q: Matrix<T>,
+    pub r: Matrix<T>
+}
+
+/// Eigen-decomposition of a symmetric matrix. The eigenvalues are sorted in ascending order
+/// as a column vector and the corresponding eigenvectors are the columns of `vectors`.
+#[derive(Debug, Clone)]
+pub struct Eigh<T = f32> {
+    pub values: Matrix<T>,
+    pub vectors: Matrix<T>
+}
+
+/// Thin singular value decomposition `A = U @ diag(s) @ Vt` with `k = min(m, n)` singular
+/// values sorted in descending order, `U` of shape `(m, k)` and `Vt` of shape `(k, n)`.
+#[derive(Debug, Clone)]
+pub struct Svd<T = f32> {
+    pub u: Matrix<T>,
+    pub s: Matrix<T>,
+    pub vt: Matrix<T>
+}
+
+// dense row-major f64 working copy
+#[derive(Clone)]
+struct Dense {
+    data: Vec<f64>,
+    rows: usize,
+    cols: usize
+}
+
+impl Dense {
+    fn from<T: Float>(mat: &Matrix<T>) -> Self {
+        let (rows, cols) = mat.shape();
+        Self { data: mat.data().iter().map(|&x| x.to_f64()).collect(), rows, cols }
+    }
+
+    fn zeros(rows: usize, cols: usize) -> Self {
+        Self { data: vec![0.; rows * cols], rows, cols }
+    }
+
+    fn identity(n: usize) -> Self {
+        let mut id = Self::zeros(n, n);
+        for i in 0..n {
+            id.set(i, i, 1.);
+        }
+        id
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        self.data[i * self.cols + j]
+    }
+
+    fn set(&mut self, i: usize, j: usize, x: f64) {
+        self.data[i * self.cols + j] = x;
+    }
+
+    fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t.set(j, i, self.get(i, j));
+            }
+        }
+        t
+    }
+
+    fn into_matrix<T: Float>(self) -> Matrix<T> {
+        let data = self.data.into_iter().map(T::from_f64).collect();
+        Matrix::from_vec(data, (self.rows, self.cols), false)
+    }
+}
+
+fn check_square<T: Float>(mat: &Matrix<T>, op: &str) -> Result<usize, MatrixError> {
+    let (rows, cols) = mat.shape();
+    if rows != cols {
+        return Err(MatrixError::NotSquare { shape: mat.shape(), op: op.to_string() });
+    }
+    Ok(rows)
+}
+
+fn check_rows<T: Float>(a: &Matrix<T>, b: &Matrix<T>, op: &str) -> Result<(), MatrixError> {
+    if a.shape().0 != b.shape().0 {
+        return Err(MatrixError::ShapeMismatchError {
+            a_shape: a.shape(),
+            b_shape: b.shape(),
+            op: op.to_string()
+        });
+    }
+    Ok(())
+}
+
+impl<T: Float> Lu<T> {
+    pub fn det(&self) -> T {
+        if self.singular {
+            return T::zero();
+        }
+        let n = self.u.shape().0;
+        let det = (0..n).fold(self.sign, |det, i| det * self.u.get(i, i).to_f64());
+        T::from_f64(det)
+    }
+
+    /// Solves `A @ X = B` for every column of `B`.
+    pub fn solve(&self, b: &Matrix<T>) -> MatrixResult<T> {
+        check_rows(&self.l, b, "solve")?;
+        if self.singular {
+            return Err(MatrixError::SingularMatrix { op: "solve".to_string() });
+        }
+        let (l, u) = (Dense::from(&self.l), Dense::from(&self.u));
+        let b = Dense::from(b);
+        let (n, k) = (l.rows, b.cols);
+
+        let mut x = Dense::zeros(n, k);
+        for c in 0..k {
+            // forward substitution L y = P b
+            let mut y = vec![0.; n];
+            for i in 0..n {
+                let sum = (0..i).map(|j| l.get(i, j) * y[j]).sum::<f64>();
+                y[i] = b.get(self.perm[i], c) - sum;
+            }
+            // back substitution U x = y
+            for i in (0..n).rev() {
+                let sum = (i + 1..n).map(|j| u.get(i, j) * x.get(j, c)).sum::<f64>();
+                x.set(i, c, (y[i] - sum) / u.get(i, i));
+            }
+        }
+        Ok(x.into_matrix())
+    }
+}
+
+impl<T: Float> Matrix<T> {
+
+    /// LU decomposition with partial pivoting of a square matrix. Singular matrices
+    /// are decomposed as well, but cannot be used to solve a system.
+    pub fn lu(&self) -> Result<Lu<T>, MatrixError> {
+        let n = check_square(self, "lu")?;
+        let mut a = Dense::from(self);
+        let mut perm = (0..n).collect::<Vec<usize>>();
+        let mut sign = 1.;
+        let mut singular = false;
+        let scale = a.data.iter().fold(0., |max: f64, x| max.max(x.abs())).max(1.);
+
+        for k in 0..n {
+            let pivot = (k..n)
+                .max_by(|&i, &j| a.get(i, k).abs().total_cmp(&a.get(j, k).abs()))
+                .unwrap();
+            if pivot != k {
+                for j in 0..n {
+                    let tmp = a.get(k, j);
+                    a.set(k, j, a.get(pivot, j));
+                    a.set(pivot, j, tmp);
+                }
+                perm.swap(k, pivot);
+                sign = -sign;
+            }
+            if a.get(k, k).abs() <= EPS * scale {
+                singular = true;
+                continue;
+            }
+            for i in k + 1..n {
+                let factor = a.get(i, k) / a.get(k, k);
+                a.set(i, k, factor);
+                for j in k + 1..n {
+                    a.set(i, j, a.get(i, j) - factor * a.get(k, j));
+                }
+            }
+        }
+
+        let mut l = Dense::identity(n);
+        let mut u = Dense::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                if j < i {
+                    l.set(i, j, a.get(i, j));
+                } else {
+                    u.set(i, j, a.get(i, j));
+                }
+            }
+        }
+        Ok(Lu { l: l.into_matrix(), u: u.into_matrix(), perm, sign, singular })
+    }
+
+    /// QR decomposition using Householder reflections.
+    pub fn qr(&self) -> Result<Qr<T>, MatrixError> {
+        let (m, n) = self.shape();
+        let mut r = Dense::from(self);
+        let mut q = Dense::identity(m);
+
+        for k in 0..n.min(m.saturating_sub(1)) {
+            let norm = (k..m).map(|i| r.get(i, k).powi(2)).sum::<f64>().sqrt();
+            if norm <= EPS {
+                continue;
+            }
+            let alpha = if r.get(k, k) > 0. { -norm } else { norm };
+            let mut v = (k..m).map(|i| r.get(i, k)).collect::<Vec<f64>>();
+            v[0] -= alpha;
+            let v_norm = v.iter().map(|x| x * x).sum::<f64>();
+            if v_norm <= EPS * EPS {
+                continue;
+            }
+
+            // R = H R and Q = Q H with H = I - 2 v v^T / (v^T v)
+            for j in 0..n {
+                let dot = (k..m).map(|i| v[i - k] * r.get(i, j)).sum::<f64>();
+                for i in k..m {
+                    r.set(i, j, r.get(i, j) - 2. * v[i - k] * dot / v_norm);
+                }
+            }
+            for i in 0..m {
+                let dot = (k..m).map(|j| q.get(i, j) * v[j - k]).sum::<f64>();
+                for j in k..m {
+                    q.set(i, j, q.get(i, j) - 2. * dot * v[j - k] / v_norm);
+                }
+            }
+        }
+
+        // clear the round-off below the diagonal
+        for i in 0..m {
+            for j in 0..i.min(n) {
+                r.set(i, j, 0.);
+            }
+        }
+        Ok(Qr { q: q.into_matrix(), r: r.into_matrix() })
+    }
+
+    /// Cholesky decomposition `A = L @ L.T` of a symmetric positive definite matrix,
+    /// returning the lower triangular `L`. Only the lower triangle of `A` is read.
+    pub fn cholesky(&self) -> MatrixResult<T> {
+        let n = check_square(self, "cholesky")?;
+        let a = Dense::from(self);
+        let mut l = Dense::zeros(n, n);
+
+        for j in 0..n {
+            let diag = a.get(j, j) - (0..j).map(|k| l.get(j, k).powi(2)).sum::<f64>();
+            if diag <= EPS {
+                return Err(MatrixError::NotPositiveDefinite { op: "cholesky".to_string() });
+            }
+            let diag = diag.sqrt();
+            l.set(j, j, diag);
+            for i in j + 1..n {
+                let sum = (0..j).map(|k| l.get(i, k) * l.get(j, k)).sum::<f64>();
+                l.set(i, j, (a.get(i, j) - sum) / diag);
+            }
+        }
+        Ok(l.into_matrix())
+    }
+
+    /// Eigen-decomposition of a symmetric matrix using the cyclic Jacobi method.
+    /// Only the upper triangle of `A` is read.
+    pub fn eigh(&self) -> Result<Eigh<T>, MatrixError> {
+        let n = check_square(self, "eigh")?;
+        let mut a = Dense::from(self);
+        for i in 0..n {
+            for j in 0..i {
+                a.set(i, j, a.get(j, i));
+            }
+        }
+        let mut v = Dense::identity(n);
+
+        for _ in 0..MAX_SWEEPS {
+            let off = (0..n)
+                .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a.get(i, j).powi(2))
+                .sum::<f64>();
+            if off <= EPS * EPS {
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a.get(p, q).abs() <= f64::MIN_POSITIVE {
+                        continue;
+                    }
+                    // rotation that zeroes a[p][q]
+                    let theta = (a.get(q, q) - a.get(p, p)) / (2. * a.get(p, q));
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                    let c = 1. / (t * t + 1.).sqrt();
+                    let s = t * c;
+
+                    for k in 0..n {
+                        let (akp, akq) = (a.get(k, p), a.get(k, q));
+                        a.set(k, p, c * akp - s * akq);
+                        a.set(k, q, s * akp + c * akq);
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a.get(p, k), a.get(q, k));
+                        a.set(p, k, c * apk - s * aqk);
+                        a.set(q, k, s * apk + c * aqk);
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (v.get(k, p), v.get(k, q));
+                        v.set(k, p, c * vkp - s * vkq);
+                        v.set(k, q, s * vkp + c * vkq);
+                    }
+                }
+            }
+        }
+
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by(|&i, &j| a.get(i, i).total_cmp(&a.get(j, j)));
+
+        let mut values = Dense::zeros(n, 1);
+        let mut vectors = Dense::zeros(n, n);
+        for (col, &k) in order.iter().enumerate() {
+            values.set(col, 0, a.get(k, k));
+            for i in 0..n {
+                vectors.set(i, col, v.get(i, k));
+            }
+        }
+        Ok(Eigh { values: values.into_matrix(), vectors: vectors.into_matrix() })
+    }
+
+    /// Thin singular value decomposition using one-sided Jacobi rotations.
+    pub fn svd(&self) -> Result<Svd<T>, MatrixError> {
+        let (m, n) = self.shape();
+        if m < n {
+            // A.T = V S U.T
+            let svd = self.t().detach().svd()?;
+            return Ok(Svd { u: svd.vt.t().detach(), s: svd.s, vt: svd.u.t().detach() });
+        }
+
+        // orthogonalize the columns of U = A V
+        let mut u = Dense::from(self);
+        let mut v = Dense::identity(n);
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let alpha = (0..m).map(|i| u.get(i, p).powi(2)).sum::<f64>();
+                    let beta = (0..m).map(|i| u.get(i, q).powi(2)).sum::<f64>();
+                    let gamma = (0..m).map(|i| u.get(i, p) * u.get(i, q)).sum::<f64>();
+                    if gamma.abs() <= EPS * (alpha * beta).sqrt() || gamma == 0. {
+                        continue;
+                    }
+                    rotated = true;
+                    let zeta = (beta - alpha) / (2. * gamma);
+                    let t = zeta.signum() / (zeta.abs() + (zeta * zeta + 1.).sqrt());
+                    let t = if zeta == 0. { 1. } else { t };
+                    let c = 1. / (t * t + 1.).sqrt();
+                    let s = c * t;
+                    for i in 0..m {
+                        let (uip, uiq) = (u.get(i, p), u.get(i, q));
+                        u.set(i, p, c * uip - s * uiq);
+                        u.set(i, q, s * uip + c * uiq);
+                    }
+                    for i in 0..n {
+                        let (vip, viq) = (v.get(i, p), v.get(i, q));
+                        v.set(i, p, c * vip - s * viq);
+                        v.set(i, q, s * vip + c * viq);
+                    }
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let norms = (0..n)
+            .map(|j| (0..m).map(|i| u.get(i, j).powi(2)).sum::<f64>().sqrt())
+            .collect::<Vec<f64>>();
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by(|&i, &j| norms[j].total_cmp(&norms[i]));
+
+        let mut u_out = Dense::zeros(m, n);
+        let mut s = Dense::zeros(n, 1);
+        let mut vt = Dense::zeros(n, n);
+        for (col, &k) in order.iter().enumerate() {
+            s.set(col, 0, norms[k]);
+            for i in 0..m {
+                let x = if norms[k] > EPS { u.get(i, k) / norms[k] } else { 0. };
+                u_out.set(i, col, x);
+            }
+            for i in 0..n {
+                vt.set(col, i, v.get(i, k));
+            }
+        }
+        Ok(Svd { u: u_out.into_matrix(), s: s.into_matrix(), vt: vt.into_matrix() })
+    }
+
+    /// Solves `A @ X = B` for a square, non-singular `A`.
+    pub fn solve(&self, b: &Matrix<T>) -> MatrixResult<T> {
+        self.lu()?.solve(b)
+    }
+
+    pub fn inverse(&self) -> MatrixResult<T> {
+        let n = check_square(self, "inverse")?;
+        let lu = self.lu()?;
+        if lu.singular {
+            return Err(MatrixError::SingularMatrix { op: "inverse".to_string() });
+        }
+        lu.solve(&Dense::identity(n).into_matrix())
+    }
+
+    pub fn det(&self) -> Result<T, MatrixError> {
+        Ok(self.lu()?.det())
+    }
+
+    /// Least squares solution `X` minimizing `||A @ X - B||` for every column of `B`,
+    /// for an `A` with at least as many rows as columns and full column rank.
+    pub fn lstsq(&self, b: &Matrix<T>) -> MatrixResult<T> {
+        check_rows(self, b, "lstsq")?;
+        let (m, n) = self.shape();
+        if m < n {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: self.shape(),
+                b_shape: b.shape(),
+                op: "lstsq".to_string()
+            });
+        }
+
+        let Qr { q, r } = self.qr()?;
+        let (q, r) = (Dense::from(&q), Dense::from(&r));
+        let b = Dense::from(b);
+        let qt_b = {
+            let qt = q.transpose();
+            let mut qt_b = Dense::zeros(m, b.cols);
+            for i in 0..m {
+                for c in 0..b.cols {
+                    qt_b.set(i, c, (0..m).map(|k| qt.get(i, k) * b.get(k, c)).sum());
+                }
+            }
+            qt_b
+        };
+
+        let scale = r.data.iter().fold(0., |max: f64, x| max.max(x.abs())).max(1.);
+        let mut x = Dense::zeros(n, b.cols);
+        for c in 0..b.cols {
+            for i in (0..n).rev() {
+                if r.get(i, i).abs() <= EPS * scale {
+                    return Err(MatrixError::SingularMatrix { op: "lstsq".to_string() });
+                }
+                let sum = (i + 1..n).map(|j| r.get(i, j) * x.get(j, c)).sum::<f64>();
+                x.set(i, c, (qt_b.get(i, c) - sum) / r.get(i, i));
+            }
+        }
+        Ok(x.into_matrix())
+    }
+}
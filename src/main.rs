@@ -1,9 +1,12 @@
 use std::error::Error;
 
-use neural_network::{NN, Matrix};
+use neural_network::NN;
 use plotlib::{repr::Plot, view::ContinuousView, page::Page, style::LineStyle};
 
 use std::env;
+
+// the xor training below is kept as a scratch pad, disabled by the early return
+#[allow(unreachable_code)]
 fn main() -> Result<(), Box<dyn Error>>{
     env::set_var("RUST_BACKTRACE", "1");
 
@@ -77,10 +80,7 @@ fn main() -> Result<(), Box<dyn Error>>{
 
     Page::single(&v).save("test.svg").unwrap();
         
-    nn.forward((&x_train[0]).into())?.print();
-    nn.forward((&x_train[1]).into())?.print();
-    nn.forward((&x_train[2]).into())?.print();
-    nn.forward((&x_train[3]).into())?.print();
+    nn.predict(&x_train)?.print();
 
     Ok(())
 }
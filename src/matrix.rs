@@ -1,6 +1,10 @@
-use std::{rc::Rc, usize};
+use std::sync::Arc;
 use rand::prelude::*;
 use crate::{
+    Float,
+    RowGrad,
+    anomaly,
+    hooks::Hooks,
     Operator, 
     BinaryOpType::{
         Add,
@@ -8,20 +12,38 @@ use crate::{
         Mul,
         Div,
         MatMul,
+        Maximum,
+        Minimum,
     },
     UnaryOpType::{
         Sigmoid,
         Transpose,
         Broadcast,
-        Sum
+        Sum,
+        Exp,
+        Ln,
+        Log1p,
+        Sqrt,
+        Abs,
+        Neg,
+        Sin,
+        Cos,
+        Tanh,
+        Reciprocal,
+        Clamp,
+        Reshape,
     },
     BinaryScalarOpType::{
         MulScalar,
-        Powf32
+        Powf32,
+        AddScalar,
+        SubScalar,
+        DivScalar,
     }, 
     error::MatrixError::{
         BroadCastError,
         ShapeMismatchError, 
+        InvalidAxis,
         self
     }
 };
@@ -36,21 +58,25 @@ fn get_id() -> usize {
 #[derive(Debug)]
 struct Matrix_<T> {
     id: usize,
-    data: Rc<Vec<T>>,
+    data: Arc<Vec<T>>,
     shape: (usize, usize), // (rows, cols), i.e., matrix in row-major form
     with_grad: bool,
-    optype: Option<Operator>
+    optype: Option<Operator<T>>,
+    hooks: Hooks<T>,
+    // anomaly found in this result or one of its inputs, see `anomaly::check_output`
+    anomaly: Option<Arc<MatrixError>>
 }
 
+/// A matrix of `f32` elements by default, or of any other `Float` type such as `f64`.
 #[derive(Debug, Clone)]
-pub struct Matrix(Rc<Matrix_<f32>>);
+pub struct Matrix<T = f32>(Arc<Matrix_<T>>);
 
-type MatrixResult = Result<Matrix, MatrixError>;
+type MatrixResult<T> = Result<Matrix<T>, MatrixError>;
 
-impl Matrix_<f32> {
+impl <T: Float> Matrix_<T> {
     fn randn(
-        from: f32, 
-        to: f32, 
+        from: T, 
+        to: T, 
         (m, n): (usize, usize), 
         with_grad: bool
     ) -> Self {  
@@ -60,14 +86,16 @@ impl Matrix_<f32> {
         let mut rng = rand::thread_rng();
         let data = (0..size)
             .map(|_| rng.gen_range(from..to))
-            .collect::<Vec<f32>>();
+            .collect::<Vec<T>>();
         
         Self { 
             id: get_id(), 
-            data: Rc::new(data),
+            data: Arc::new(data),
             shape: (m, n),
             with_grad,
-            optype: None 
+            optype: None,
+            hooks: Hooks::default(),
+            anomaly: None
         }
     }
 }
@@ -76,26 +104,28 @@ impl <T> Matrix_<T> {
     pub fn new(
         data: Vec<T>, 
         (m, n): (usize, usize), 
-        op: Option<Operator>, 
+        op: Option<Operator<T>>, 
         with_grad: bool
     ) -> Self {  
         
         Self { 
             id: get_id(), 
-            data: Rc::new(data),
+            data: Arc::new(data),
             shape: (m, n),
             with_grad,
-            optype: op 
+            optype: op,
+            hooks: Hooks::default(),
+            anomaly: None
         }
     }
 }
 
 macro_rules! binary_operator {
-    ($name: ident, $op: tt, $op_type: expr) => {
+    ($name: ident, |$a: ident, $b: ident| $body: expr, $op_type: expr) => {
         
-        pub fn $name(&self, other: &Self) -> MatrixResult {
+        pub fn $name(&self, other: &Self) -> MatrixResult<T> {
 
-            let shape = Matrix::broadcast_shape(self.shape(), other.shape());
+            let shape = Self::broadcast_shape(self.shape(), other.shape());
 
             let l_broadcast = shape != self.shape();
             let r_broadcast = shape != other.shape();
@@ -109,45 +139,97 @@ macro_rules! binary_operator {
     
             let (rows, cols) = shape;
     
-            let mut data = vec![0.; rows * cols];
+            let mut data = vec![T::zero(); rows * cols];
 
             for i in 0..rows {
                 for j in 0..cols {
-                    data[i * cols + j] = lhs.get(i, j) $op rhs.get(i, j);
+                    let ($a, $b) = (lhs.get(i, j), rhs.get(i, j));
+                    data[i * cols + j] = $body;
                 }
             }
 
             let req_grad = lhs.requires_grad() || rhs.requires_grad();
-            let op = Some(Operator::Binary(lhs, rhs, $op_type));
+            let op = Operator::Binary(lhs, rhs, $op_type);
     
-            Ok(Self(Rc::new(Matrix_::new(data, shape, op, req_grad))))
+            let mat = Self::from_op(data, shape, op, req_grad);
+            anomaly::raise(&mat)?;
+            Ok(mat)
+        }
+        
+    };
+    ($name: ident, $op: tt, $op_type: expr) => {
+        binary_operator!($name, |a, b| a $op b, $op_type);
+    };
+}
+
+macro_rules! binary_scalar_operator {
+    ($name: ident, |$x: ident, $s: ident| $body: expr, $op_type: expr) => {
+        
+        pub fn $name(&self, other: T) -> Self {
+
+            let $s = other;
+            let data = self.data()
+                .iter()
+                .map(|&$x| $body)
+                .collect::<Vec<T>>();
+
+            let op = Operator::BinaryScalar(self.clone(), other, $op_type);
+
+            Self::from_op(data, self.shape(), op, self.requires_grad())
+        }
+        
+    };
+}
+
+macro_rules! unary_operator {
+    ($name: ident, |$x: ident| $body: expr, $op_type: expr) => {
+        
+        pub fn $name(&self) -> Self {
+
+            let data = self.data()
+                .iter()
+                .map(|&$x| $body)
+                .collect::<Vec<T>>();
+
+            let op = Operator::Unary(self.clone(), $op_type);
+
+            Self::from_op(data, self.shape(), op, self.requires_grad())
         }
         
     };
 }
 
+impl<T: Float> Matrix<T> {
 
-impl Matrix {
+    // constructs the result of an operation, checking it for anomalies if enabled
+    pub(crate) fn from_op(data: Vec<T>, shape: (usize, usize), op: Operator<T>, with_grad: bool) -> Self {
+        let mut mat = Self(Arc::new(Matrix_::new(data, shape, Some(op), with_grad)));
+        if let Some(anomaly) = anomaly::check_output(&mat) {
+            // not shared yet
+            Arc::get_mut(&mut mat.0).expect("new matrix is unique").anomaly = Some(anomaly);
+        }
+        mat
+    }
 
     pub fn ones(shape: (usize, usize), with_grad: bool) -> Self {
-        Matrix::fill(shape, 1., with_grad)
+        Matrix::fill(shape, T::one(), with_grad)
     }
 
     pub fn zeros(shape: (usize, usize), with_grad: bool) -> Self {
-        Matrix::fill(shape, 0., with_grad)
+        Matrix::fill(shape, T::zero(), with_grad)
     }
 
-    pub fn fill(shape: (usize, usize), value: f32, with_grad: bool) -> Self {
+    pub fn fill(shape: (usize, usize), value: T, with_grad: bool) -> Self {
         let data = vec![value; shape.0 * shape.1];
-        Self(Rc::new(Matrix_::new(data, shape, None, with_grad)))
+        Self(Arc::new(Matrix_::new(data, shape, None, with_grad)))
     }
 
-    pub fn randn(from: f32, to: f32, shape: (usize, usize), with_grad: bool) -> Self {
-        Self(Rc::new(Matrix_::randn(from, to, shape, with_grad)))
+    pub fn randn(from: T, to: T, shape: (usize, usize), with_grad: bool) -> Self {
+        Self(Arc::new(Matrix_::randn(from, to, shape, with_grad)))
     }
 
-    pub fn from_vec(data: Vec<f32>, shape: (usize, usize), with_grad: bool) -> Self {
-        Self(Rc::new(Matrix_::new(data, shape, None, with_grad)))
+    pub fn from_vec(data: Vec<T>, shape: (usize, usize), with_grad: bool) -> Self {
+        Self(Arc::new(Matrix_::new(data, shape, None, with_grad)))
     }
 
     pub fn print(&self) {
@@ -164,16 +246,9 @@ impl Matrix {
     fn _print_comp_tree(&self, indent: usize) {
         let mat_info = format!("matrix id: {}, use grad: {}, shape: {:?}", self.id(), self.requires_grad(), self.shape());
         if let Some(op) = self.op() {
-            println!("{:indent$}{} with op: {}", " ", mat_info, op.to_string(), indent=indent);
-            match op {
-                Operator::Binary(lhs, rhs, _) => {
-                    lhs._print_comp_tree(indent+4);
-                    rhs._print_comp_tree(indent+4);
-                },
-                Operator::Unary(val, _) | 
-                Operator::BinaryScalar(val, _, _) => {
-                    val._print_comp_tree(indent+4);
-                }
+            println!("{:indent$}{} with op: {}", " ", mat_info, op, indent=indent);
+            for val in op.operands() {
+                val._print_comp_tree(indent+4);
             }
         } else {
             println!("{:indent$}{}", " ", mat_info, indent=indent);
@@ -185,8 +260,63 @@ impl Matrix {
         self._print_comp_tree(0);
     }
 
+    /// Returns a new leaf sharing the data of `self`, which does not require a gradient.
+    pub fn detach(&self) -> Self {
+        self.as_leaf(false)
+    }
+
+    pub(crate) fn as_leaf(&self, with_grad: bool) -> Self {
+        Self(Arc::new(Matrix_ { 
+            id: get_id(), 
+            data: self.0.data.clone(), 
+            shape: self.shape(), 
+            with_grad, 
+            optype: None,
+            hooks: Hooks::default(),
+            anomaly: None
+        }))
+    }
+
+    /// Subtracts `scale` times `grad` from the rows of this matrix that `grad` holds, leaving
+    /// the other rows as they are, and returns the result as a new leaf. The data is updated
+    /// in place when this matrix was its only owner; otherwise it is copied first.
+    /// `grad` must be of the shape of this matrix.
+    pub(crate) fn sub_rows(self, grad: &RowGrad<T>, scale: T) -> Self {
+        let (shape, with_grad) = (self.shape(), self.requires_grad());
+        let mut data = match Arc::try_unwrap(self.0) {
+            Ok(mat) => mat.data,
+            Err(mat) => mat.data.clone()
+        };
+
+        let cols = shape.1;
+        let values = Arc::make_mut(&mut data);
+        for (&row, grad_row) in grad.rows().iter().zip(grad.values().chunks(cols.max(1))) {
+            values[row * cols..(row + 1) * cols]
+                .iter_mut()
+                .zip(grad_row)
+                .for_each(|(x, &g)| *x = *x - scale * g);
+        }
+
+        Self(Arc::new(Matrix_ { 
+            id: get_id(), 
+            data, 
+            shape, 
+            with_grad, 
+            optype: None,
+            hooks: Hooks::default(),
+            anomaly: None
+        }))
+    }
+
     pub fn no_history(&self) -> Self{
-        Self(Rc::new(Matrix_::new(self.data().clone(), self.shape(), None, self.requires_grad())))
+        Self(Arc::new(Matrix_::new(self.data().clone(), self.shape(), None, self.requires_grad())))
+    }
+
+    /// Converts every element to `U`, e.g. from `f32` to `f64`. The result is a new leaf
+    /// with the same `requires_grad`; the computation graph does not cross precisions.
+    pub fn cast<U: Float>(&self) -> Matrix<U> {
+        let data = self.data().iter().map(|x| U::from_f64(x.to_f64())).collect();
+        Matrix::from_vec(data, self.shape(), self.requires_grad())
     }
 
     pub fn shape(&self) -> (usize, usize) {
@@ -197,24 +327,32 @@ impl Matrix {
         self.0.id
     }
 
-    pub fn op(&self) -> &Option<Operator>{
+    pub fn op(&self) -> &Option<Operator<T>>{
         &self.0.optype
     }
 
+    pub(crate) fn hooks(&self) -> &Hooks<T> {
+        &self.0.hooks
+    }
+
+    pub(crate) fn anomaly(&self) -> Option<&Arc<MatrixError>> {
+        self.0.anomaly.as_ref()
+    }
+
     pub fn requires_grad(&self) -> bool {
         self.0.with_grad
     }
 
-    pub fn data(&self) -> &Vec<f32> {
+    pub fn data(&self) -> &Vec<T> {
         &self.0.data
     }
 
-    pub fn get(&self, row_i: usize, col_j: usize) -> f32 {
+    pub fn get(&self, row_i: usize, col_j: usize) -> T {
         let (_, cols) = self.shape();
         self.0.data[row_i * cols + col_j]
     }
 
-    pub fn matmul(&self, other: &Self) -> MatrixResult {
+    pub fn matmul(&self, other: &Self) -> MatrixResult<T> {
 
         let (a_rows, a_cols) = self.shape();
         let (b_rows, b_cols) = other.shape();
@@ -227,7 +365,7 @@ impl Matrix {
             });
         }
         let shape = (a_rows, b_cols);
-        let mut data = vec![0.; a_rows * b_cols];
+        let mut data = vec![T::zero(); a_rows * b_cols];
         for i in 0..a_rows {
             for j in 0..b_cols {
                 for k in 0..a_cols {
@@ -236,76 +374,85 @@ impl Matrix {
             }   
         }
 
-        let op = Some(Operator::Binary(self.clone(), other.clone(), MatMul));
+        let op = Operator::Binary(self.clone(), other.clone(), MatMul);
 
-        Ok(Self(Rc::new(Matrix_::new(data, shape, op, self.requires_grad() || other.requires_grad()))))
+        let mat = Self::from_op(data, shape, op, self.requires_grad() || other.requires_grad());
+        anomaly::raise(&mat)?;
+        Ok(mat)
     }
 
-    pub fn mul_scalar(&self, other: f32) -> Self {
-
-        let (rows, cols) = self.shape();
-    
-        let mut data = vec![0.; rows * cols];
-        for i in 0..rows {
-            for j in 0..cols {
-                data[i * cols + j] = self.get(i, j) * other;
-            }   
-        }
-
-        let op = Some(Operator::BinaryScalar(self.clone(), other, MulScalar));
-
-        Self(Rc::new(Matrix_::new(data, self.shape(), op, self.requires_grad())))
-    }
-
-    pub fn powf(&self, other: f32) -> Self {
-
-        let (rows, cols) = self.shape();
-    
-        let mut data = vec![0.; rows * cols];
-        for i in 0..rows {
-            for j in 0..cols {
-                data[i * cols + j] = self.get(i, j).powf(other);
-            }   
-        }
-
-        let op = Some(Operator::BinaryScalar(self.clone(), other, Powf32));
-
-        Self(Rc::new(Matrix_::new(data, self.shape(), op, self.requires_grad())))
-    }
+    binary_scalar_operator!(mul_scalar, |x, s| x * s, MulScalar);
+    binary_scalar_operator!(powf, |x, s| x.powf(s), Powf32);
+    binary_scalar_operator!(add_scalar, |x, s| x + s, AddScalar);
+    binary_scalar_operator!(sub_scalar, |x, s| x - s, SubScalar);
+    binary_scalar_operator!(div_scalar, |x, s| x / s, DivScalar);
 
     binary_operator!(add, +, Add);
     binary_operator!(mul, *, Mul);
     binary_operator!(sub, -, Sub);
     binary_operator!(div, /, Div);
+    binary_operator!(maximum, |a, b| a.max(b), Maximum);
+    binary_operator!(minimum, |a, b| a.min(b), Minimum);
+
+    unary_operator!(exp, |x| x.exp(), Exp);
+    unary_operator!(ln, |x| x.ln(), Ln);
+    unary_operator!(log1p, |x| x.ln_1p(), Log1p);
+    unary_operator!(sqrt, |x| x.sqrt(), Sqrt);
+    unary_operator!(abs, |x| x.abs(), Abs);
+    unary_operator!(neg, |x| -x, Neg);
+    unary_operator!(sin, |x| x.sin(), Sin);
+    unary_operator!(cos, |x| x.cos(), Cos);
+    unary_operator!(tanh, |x| x.tanh(), Tanh);
+    unary_operator!(reciprocal, |x| T::one() / x, Reciprocal);
+    unary_operator!(sigmoid, |x| _sigmoid(x), Sigmoid);
+
+    /// Clamps every element into `[min, max]`. The gradient passes through
+    /// for elements inside the interval, including its bounds, and is zero elsewhere.
+    pub fn clamp(&self, min: T, max: T) -> Self {
+        let data = self.data()
+            .iter()
+            .map(|x| x.clamp(min, max))
+            .collect::<Vec<T>>();
+
+        let op = Operator::Unary(self.clone(), Clamp(min, max));
+
+        Self::from_op(data, self.shape(), op, self.requires_grad())
+    }
 
-    pub fn t(&self) -> Matrix {
+    pub fn t(&self) -> Self {
         let (rows, cols) = self.shape();
-        let mut data = vec![0.; rows * cols];
+        let mut data = vec![T::zero(); rows * cols];
         for i in 0..rows {
             for j in 0..cols {
-                data[i * cols + j] += self.get(i, j);
+                data[j * rows + i] = self.get(i, j);
             }   
         }
-        let op = Some(Operator::Unary(self.clone(), Transpose));
+        let op = Operator::Unary(self.clone(), Transpose);
 
         // exchange cols and rows to get transpose matrix
-        Self(Rc::new(Matrix_::new(data, (cols, rows), op, self.requires_grad())))
+        Self::from_op(data, (cols, rows), op, self.requires_grad())
     }
 
-    pub fn sigmoid(&self) -> Matrix {
-        let (rows, cols) = self.shape();
-        let mut data = vec![0.; rows * cols];
-        for i in 0..rows {
-            for j in 0..cols {
-                data[i * cols + j] += _sigmoid(self.get(i, j));
-            }   
+    /// Reinterprets the row-major data of this matrix as shape `(rows, cols)`.
+    pub fn reshape(&self, (rows, cols): (usize, usize)) -> MatrixResult<T> {
+        if rows * cols != self.data().len() {
+            return Err(ShapeMismatchError { 
+                a_shape: self.shape(), 
+                b_shape: (rows, cols), 
+                op: "reshape".to_string() 
+            });
         }
-        let op = Some(Operator::Unary(self.clone(), Sigmoid));
 
-        Self(Rc::new(Matrix_::new(data, (rows, cols), op, self.requires_grad())))
+        let op = Operator::Unary(self.clone(), Reshape);
+
+        let mat = Self::from_op(self.data().clone(), (rows, cols), op, self.requires_grad());
+        anomaly::raise(&mat)?;
+        Ok(mat)
     }
 
-    pub fn broadcast_as(&self, (rows, cols): (usize, usize)) -> MatrixResult {
+    /// Repeats the rows and/or columns of size 1 to obtain shape `(rows, cols)`.
+    /// A dimension can only be broadcast if its size is 1 or already equal to the target size.
+    pub fn broadcast_as(&self, (rows, cols): (usize, usize)) -> MatrixResult<T> {
     
         // (1, 2) => (3, 2)
 
@@ -318,76 +465,77 @@ impl Matrix {
         // [4] => [4, 4, 4]
         // [5]    [5, 5, 5]
 
+        // (1, 1) => (2, 2)
 
-        // (1, 1) => (1, 4)
+        // [2] => [2, 2]
+        //        [2, 2]
 
-        // [2] => 
+        let (self_rows, self_cols) = self.shape();
+        
+        if !(self_rows == rows || self_rows == 1) || !(self_cols == cols || self_cols == 1) {
+            return Err(BroadCastError { 
+                got_shape: self.shape(), 
+                expected_shape: (rows, cols) 
+            });
+        }
 
-        let data = match (self.shape(), (rows, cols)) {
-            ((1, 1), (2..=usize::MAX, 1)) | 
-            ((1, 2..=usize::MAX), _) => {
-                let mut data = vec![];
-                for _ in 0..rows {
-                    data.extend(self.data().iter());
-                }
-                data
-            },
-            ((1, 1), (1, 2..=usize::MAX)) |
-            ((2..=usize::MAX, 1), _) => {
-                let mut data = vec![0.; rows * cols];
-                for i in 0..rows {
-                    for j in 0..cols {
-                        data[i * cols + j] = self.get(i, 0);
-                    }
-                }
-                data
-            },
-            _ => {
-                return Err(BroadCastError { 
-                    got_shape: self.shape(), 
-                    expected_shape: (rows, cols) 
-                });
+        let mut data = vec![T::zero(); rows * cols];
+        for i in 0..rows {
+            for j in 0..cols {
+                data[i * cols + j] = self.get(i % self_rows, j % self_cols);
             }
-        };
+        }
         
-        let op = Some(Operator::Unary(self.clone(), Broadcast));
+        let op = Operator::Unary(self.clone(), Broadcast);
 
-        Ok(Self(Rc::new(Matrix_::new(data, (rows, cols), op, self.requires_grad()))))
+        let mat = Self::from_op(data, (rows, cols), op, self.requires_grad());
+        anomaly::raise(&mat)?;
+        Ok(mat)
     }
 
-    pub fn sum(&self, axis: usize) -> MatrixResult {
+    /// Sums the elements along `axis`, following the numpy convention:
+    /// 
+    /// - axis 0 sums over the rows, collapsing a `(rows, cols)` matrix into `(1, cols)`
+    /// - axis 1 sums over the columns, collapsing a `(rows, cols)` matrix into `(rows, 1)`
+    /// 
+    /// Any other axis results in an `InvalidAxis` error.
+    pub fn sum(&self, axis: usize) -> MatrixResult<T> {
         
         let (rows, cols) = self.shape();
 
         let (data, new_shape) = match axis {
             0 => {
-                let shape = (rows, 1 as usize);
+                let shape = (1, cols);
 
-                let mut data = vec![0.; rows];
-                for i in 0..rows {
-                    for j in 0..cols {
-                        data[i] += self.get(i, j);
+                let mut data = vec![T::zero(); cols];
+                for (j, val) in data.iter_mut().enumerate() {
+                    for i in 0..rows {
+                        *val += self.get(i, j);
                     }
                 }
                 (data, shape)
             },
             1 => {
-                let shape = (1 as usize, cols);
+                let shape = (rows, 1);
 
-                let mut data = vec![0.; cols];
-                for j in 0..cols {
-                    for i in 0..rows {
-                        data[j] += self.get(i, j);
+                let mut data = vec![T::zero(); rows];
+                for (i, val) in data.iter_mut().enumerate() {
+                    for j in 0..cols {
+                        *val += self.get(i, j);
                     }
                 }
                 (data, shape)
             }
-            _ => (self.data().clone(), (rows, cols))
+            _ => {
+                return Err(InvalidAxis { axis, op: "sum".to_string() });
+            }
         };
         
-        let op = Some(Operator::Unary(self.clone(), Sum));
+        let op = Operator::Unary(self.clone(), Sum);
 
-        Ok(Self(Rc::new(Matrix_::new(data, new_shape, op, self.requires_grad()))))
+        let mat = Self::from_op(data, new_shape, op, self.requires_grad());
+        anomaly::raise(&mat)?;
+        Ok(mat)
     }
 
     pub fn broadcast_shape(lhs: (usize, usize), rhs: (usize, usize)) -> (usize, usize) {
@@ -415,34 +563,34 @@ impl Matrix {
 
 }
 
-fn _sigmoid(x: f32) -> f32 {
-    1./(1. + (-x).exp())
+fn _sigmoid<T: Float>(x: T) -> T {
+    T::one()/(T::one() + (-x).exp())
 }
 
-impl Into<Matrix> for Vec<f32> {
-    fn into(self) -> Matrix {
-        let len = self.len();
-        Matrix::from_vec(self, (len, 1), false)
+impl<T: Float> From<Vec<T>> for Matrix<T> {
+    fn from(value: Vec<T>) -> Self {
+        let len = value.len();
+        Matrix::from_vec(value, (len, 1), false)
     }
 }
 
-impl Into<Matrix> for &Vec<f32> {
-    fn into(self) -> Matrix {
-        let len = self.len();
-        Matrix::from_vec(self.clone(), (len, 1), false)
+impl<T: Float> From<&Vec<T>> for Matrix<T> {
+    fn from(value: &Vec<T>) -> Self {
+        let len = value.len();
+        Matrix::from_vec(value.clone(), (len, 1), false)
     }
 }
 
-impl Into<Matrix> for Vec<Vec<f32>> {
-    fn into(self) -> Matrix {
-        let rows = self.len();
-        let cols = if let Some(cols) = self.get(0) {
+impl<T: Float> From<Vec<Vec<T>>> for Matrix<T> {
+    fn from(value: Vec<Vec<T>>) -> Self {
+        let rows = value.len();
+        let cols = if let Some(cols) = value.first() {
             cols.len()
         } else {
             0
         };
 
-        for v in self.iter() {
+        for v in value.iter() {
             if v.len() == cols {
                 continue;
             } else {
@@ -450,37 +598,17 @@ impl Into<Matrix> for Vec<Vec<f32>> {
             }
         }
         
-        let data = self
+        let data = value
             .into_iter()
             .flat_map(|v| v.into_iter())
-            .collect::<Vec<f32>>();
+            .collect::<Vec<T>>();
 
         Matrix::from_vec(data, (rows, cols), false)
     }
 }
 
-impl Into<Matrix> for &Vec<Vec<f32>> {
-    fn into(self) -> Matrix {
-        let rows = self.len();
-        let cols = if let Some(cols) = self.get(0) {
-            cols.len()
-        } else {
-            0
-        };
-
-        for v in self.iter() {
-            if v.len() == cols {
-                continue;
-            } else {
-                return Matrix::from_vec(vec![], (0, 0), false);
-            }
-        }
-        
-        let data = self.clone()
-            .into_iter()
-            .flat_map(|v| v.into_iter())
-            .collect::<Vec<f32>>();
-
-        Matrix::from_vec(data, (rows, cols), false)
+impl<T: Float> From<&Vec<Vec<T>>> for Matrix<T> {
+    fn from(value: &Vec<Vec<T>>) -> Self {
+        value.clone().into()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,310 @@
+use std::{error::Error, fmt::Debug, fs, path::Path};
+
+use crate::{Matrix, Parameter, error::{MatrixError, NNError}};
+
+/// A building block of a model, such as a layer or a container of layers.
+///
+/// Modules work on column-major batches, i.e., `forward` takes a matrix of shape
+/// `(features, samples)`. Every parameter returned by `parameters` is trained by `NN`,
+/// stored by `save` and listed by `summary`.
+pub trait Module: ModuleClone + Debug + Send + Sync {
+
+    /// Applies the module to `xs` of shape `(features, samples)`.
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError>;
+
+    /// One line description of the module, e.g. `Dense(2 -> 4, Sigmoid)`.
+    fn describe(&self) -> String;
+
+    /// Trainable parameters in a fixed order, including those of sub-modules.
+    fn parameters(&self) -> Vec<&Parameter> {
+        vec![]
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![]
+    }
+
+    /// Snapshot of the state that is not trained by gradient descent, such as running
+    /// statistics, but saved and loaded together with the parameters.
+    fn buffers(&self) -> Vec<Parameter> {
+        vec![]
+    }
+
+    fn buffers_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![]
+    }
+
+    /// Switches between training and evaluation behavior. Modules with sub-modules
+    /// must pass the mode on to them.
+    fn set_training(&mut self, _training: bool) {}
+
+    /// Sub-modules, as listed by `summary`.
+    fn children(&self) -> Vec<&dyn Module> {
+        vec![]
+    }
+
+    /// Whether `forward` draws random numbers in the current mode, as dropout does while
+    /// training. By default, whether any of the `children` does.
+    fn is_stochastic(&self) -> bool {
+        self.children().iter().any(|child| child.is_stochastic())
+    }
+
+    /// Number of features `forward` expects, if fixed by the module.
+    fn input_features(&self) -> Option<usize> {
+        None
+    }
+
+    fn num_parameters(&self) -> usize {
+        self.parameters()
+            .iter()
+            .map(|param| param.value().data().len())
+            .sum()
+    }
+
+    /// Table of this module and its sub-modules with their number of parameters.
+    fn summary(&self) -> String {
+        let mut lines = vec![(self.describe(), self.num_parameters())];
+        for child in self.children() {
+            summary_lines(child, 1, &mut lines);
+        }
+
+        let mut summary = format!("{:<48} {:>10}\n", "Module", "Parameters");
+        for (description, count) in lines.iter() {
+            summary.push_str(&format!("{:<48} {:>10}\n", description, count));
+        }
+        summary.push_str(&format!("Total parameters: {}\n", self.num_parameters()));
+        summary
+    }
+
+    /// The name and a detached copy of the value of every parameter and buffer.
+    fn state_dict(&self) -> Vec<(String, Matrix)> {
+        let mut state = self.parameters()
+            .iter()
+            .map(|param| (param.name().to_string(), param.value().detach()))
+            .collect::<Vec<(String, Matrix)>>();
+        state.extend(self.buffers()
+            .iter()
+            .map(|buffer| (buffer.name().to_string(), buffer.value().detach()))
+        );
+        state
+    }
+
+    /// Replaces the value of every parameter and buffer by the entry of the same name
+    /// in `state`. Entries without a matching parameter or buffer are ignored.
+    fn load_state_dict(&mut self, state: &[(String, Matrix)]) -> Result<(), NNError> {
+        for param in self.parameters_mut() {
+            let Some((_, value)) = state.iter().find(|(name, _)| name == param.name()) else {
+                return Err(NNError::MissingParameter { name: param.name().to_string() });
+            };
+            if value.shape() != param.value().shape() {
+                return Err(NNError::ParameterShapeMismatch {
+                    name: param.name().to_string(),
+                    expected: param.value().shape(),
+                    got: value.shape()
+                });
+            }
+            param.set(Matrix::from_vec(value.data().clone(), value.shape(), true));
+        }
+        for buffer in self.buffers_mut() {
+            let Some((_, value)) = state.iter().find(|(name, _)| name == buffer.name()) else {
+                return Err(NNError::MissingParameter { name: buffer.name().to_string() });
+            };
+            if value.shape() != buffer.value().shape() {
+                return Err(NNError::ParameterShapeMismatch {
+                    name: buffer.name().to_string(),
+                    expected: buffer.value().shape(),
+                    got: value.shape()
+                });
+            }
+            buffer.set(value.detach());
+        }
+        Ok(())
+    }
+
+    /// Writes the state of the module to `path`, one parameter per line.
+    fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>>
+    where Self: Sized
+    {
+        fs::write(path, write_state(&self.state_dict()))?;
+        Ok(())
+    }
+
+    /// Restores the state written by `save` into a module of the same architecture.
+    fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>>
+    where Self: Sized
+    {
+        let state = read_state(&fs::read_to_string(path)?)?;
+        self.load_state_dict(&state)?;
+        Ok(())
+    }
+}
+
+/// Allows cloning boxed modules, implemented for every `Module` that is `Clone`.
+pub trait ModuleClone {
+    fn clone_box(&self) -> Box<dyn Module>;
+}
+
+impl<M: Module + Clone + 'static> ModuleClone for M {
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Module> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+fn summary_lines(module: &dyn Module, depth: usize, lines: &mut Vec<(String, usize)>) {
+    lines.push((format!("{:indent$}{}", "", module.describe(), indent = 2 * depth), module.num_parameters()));
+    for child in module.children() {
+        summary_lines(child, depth + 1, lines);
+    }
+}
+
+// every line holds `name rows cols` followed by the row-major values
+fn write_state(state: &[(String, Matrix)]) -> String {
+    state
+        .iter()
+        .map(|(name, value)| {
+            let (rows, cols) = value.shape();
+            let values = value.data().iter().map(|x| x.to_string()).collect::<Vec<String>>();
+            format!("{} {} {} {}\n", name, rows, cols, values.join(" "))
+        })
+        .collect()
+}
+
+fn read_state(content: &str) -> Result<Vec<(String, Matrix)>, NNError> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let invalid = || NNError::InvalidStateFile { line: i + 1 };
+            let mut fields = line.split_whitespace();
+            let name = fields.next().ok_or_else(invalid)?;
+            let mut dim = || fields.next().and_then(|x| x.parse::<usize>().ok());
+            let (Some(rows), Some(cols)) = (dim(), dim()) else {
+                return Err(invalid());
+            };
+            let data = fields
+                .map(|x| x.parse::<f32>())
+                .collect::<Result<Vec<f32>, _>>()
+                .map_err(|_| invalid())?;
+            if data.len() != rows * cols {
+                return Err(invalid());
+            }
+            Ok((name.to_string(), Matrix::from_vec(data, (rows, cols), false)))
+        })
+        .collect()
+}
+
+/// Runs modules one after another, feeding the output of each into the next.
+///
+/// The parameters of the `i`-th module are prefixed with `i`, e.g. `0.weight`.
+#[derive(Debug, Clone, Default)]
+pub struct Sequential {
+    modules: Vec<Box<dyn Module>>,
+    checkpointing: bool
+}
+
+impl Sequential {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `module`, prefixing the names of its parameters and buffers with its index.
+    pub fn push<M: Module + 'static>(mut self, module: M) -> Self {
+        let mut module = Box::new(module);
+        let prefix = self.modules.len().to_string();
+        for param in module.parameters_mut() {
+            param.add_prefix(&prefix);
+        }
+        for buffer in module.buffers_mut() {
+            buffer.add_prefix(&prefix);
+        }
+        self.modules.push(module);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// When enabled, the forward pass only keeps the input and output of every module
+    /// in the computation graph and recomputes the rest during the backward pass.
+    /// Modules with buffers are not checkpointed, as they may update them in `forward`,
+    /// and neither are stochastic modules, whose recomputation would draw other numbers.
+    pub fn set_checkpointing(&mut self, checkpointing: bool) {
+        self.checkpointing = checkpointing;
+    }
+}
+
+impl Module for Sequential {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let mut ys = xs.clone();
+        for module in self.modules.iter() {
+            ys = if self.checkpointing && module.buffers().is_empty() && !module.is_stochastic() {
+                let module = module.clone();
+                Matrix::checkpoint(&[&ys], move |xs| module.forward(&xs[0]))?
+            } else {
+                module.forward(&ys)?
+            };
+        }
+        Ok(ys)
+    }
+
+    fn describe(&self) -> String {
+        "Sequential".to_string()
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        self.modules
+            .iter()
+            .flat_map(|module| module.parameters())
+            .collect()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        self.modules
+            .iter_mut()
+            .flat_map(|module| module.parameters_mut())
+            .collect()
+    }
+
+    fn buffers(&self) -> Vec<Parameter> {
+        self.modules
+            .iter()
+            .flat_map(|module| module.buffers())
+            .collect()
+    }
+
+    fn buffers_mut(&mut self) -> Vec<&mut Parameter> {
+        self.modules
+            .iter_mut()
+            .flat_map(|module| module.buffers_mut())
+            .collect()
+    }
+
+    fn set_training(&mut self, training: bool) {
+        for module in self.modules.iter_mut() {
+            module.set_training(training);
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Module> {
+        self.modules
+            .iter()
+            .map(|module| module.as_ref())
+            .collect()
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        self.modules.first()?.input_features()
+    }
+}
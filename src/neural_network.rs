@@ -1,68 +1,266 @@
-use std::error::Error;
+use std::{error::Error, path::Path, thread};
 
 use rand::seq::SliceRandom;
 
-use crate::{Matrix, error::NNError};
+use crate::{Activation, DataFrame, Dense, GradMap, Matrix, Module, Parameter, Regularizer, Sequential, error::{MatrixError, NNError}};
 
-#[derive(Debug)]
-pub enum Activation {
-    Sigmoid,
-    None,
+/// Gradient clipping applied between the backward pass and the weight update.
+#[derive(Debug, Clone, Copy)]
+pub enum GradClip {
+    /// Clamp every gradient element into `[-value, value]`.
+    Value(f32),
+    /// Rescale all gradients together such that their global L2 norm is at most the given value.
+    Norm(f32),
 }
 
-#[derive(Debug)]
-pub struct Layer {
-    w: Matrix,
-    b: Matrix,
-    act_func: Activation
+/// Per step of a training run: the mean squared error of the mini-batch, and the
+/// penalty of the regularizers, which is added to the loss once per step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainingHistory {
+    pub loss: Vec<f32>,
+    pub penalty: Vec<f32>
 }
 
-#[derive(Debug)]
-pub struct NN {
-    layers: Vec<Layer>,
-    learning_rate: f32
+/// Inputs of `NN::predict`, holding one sample per row.
+pub trait PredictInput {
+    /// The samples as a `(samples, features)` matrix.
+    fn to_rows(&self) -> Result<Matrix, Box<dyn Error>>;
+}
+
+impl PredictInput for Matrix {
+    fn to_rows(&self) -> Result<Matrix, Box<dyn Error>> {
+        Ok(self.clone())
+    }
 }
 
-impl Activation {
-    pub fn apply(&self, mat: Matrix) -> Matrix {
-        match self {
-            Self::Sigmoid => mat.sigmoid(),
-            Self::None => mat
+impl PredictInput for [Vec<f32>] {
+    fn to_rows(&self) -> Result<Matrix, Box<dyn Error>> {
+        // the conversion into a matrix silently drops ragged input
+        let cols = self.first().map_or(0, |row| row.len());
+        if let Some((row, values)) = self.iter().enumerate().find(|(_, row)| row.len() != cols) {
+            return Err(Box::new(NNError::RaggedRows { row, expected: cols, got: values.len() }));
         }
+        Ok(self.to_vec().into())
     }
 }
 
+impl PredictInput for Vec<Vec<f32>> {
+    fn to_rows(&self) -> Result<Matrix, Box<dyn Error>> {
+        self.as_slice().to_rows()
+    }
+}
+
+impl PredictInput for DataFrame {
+    fn to_rows(&self) -> Result<Matrix, Box<dyn Error>> {
+        Ok(self.to_matrix::<f32>()?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NN {
+    model: Sequential,
+    learning_rate: f32,
+    grad_clip: Option<GradClip>,
+    regularizers: Vec<Regularizer>,
+    threads: usize,
+    predict_batch_size: usize
+}
+
 impl NN {
+    /// Multilayer perceptron with sigmoid activations, where `config` holds the size
+    /// of every layer, starting with the number of inputs.
     pub fn new(config: Vec<usize>, learning_rate: f32) -> Self {
-        let mut layers = vec![];
-        for (inp, outp) in config
-                                        .windows(2)
-                                        .map(|x| (x[0], x[1])) 
-        {
-            let w = Matrix::randn(0., 1., (outp, inp), true);
-            let b = Matrix::randn(0., 1., (outp, 1), true);
-            layers.push(Layer { w, b, act_func: Activation::Sigmoid});
+        let model = config
+            .windows(2)
+            .fold(Sequential::new(), |model, x| model.push(Dense::new(x[0], x[1], Activation::Sigmoid)));
+        let mut nn = Self::from_model(model, learning_rate);
+        for param in nn.parameters_mut() {
+            param.add_prefix("layers");
+        }
+        nn
+    }
+
+    /// Trains an arbitrary model, which may contain user defined modules.
+    /// The model is put into evaluation mode, `train` enables the training mode
+    /// for its duration only.
+    pub fn from_model(mut model: Sequential, learning_rate: f32) -> Self {
+        model.set_training(false);
+        NN { model, learning_rate, grad_clip: None, regularizers: vec![], threads: 1, predict_batch_size: 256 }
+    }
+
+    pub fn model(&self) -> &Sequential {
+        &self.model
+    }
+
+    pub fn model_mut(&mut self) -> &mut Sequential {
+        &mut self.model
+    }
+
+    /// Panics if the bound of the clipping is negative or NaN.
+    pub fn set_grad_clip(&mut self, grad_clip: Option<GradClip>) {
+        if let Some(GradClip::Value(bound) | GradClip::Norm(bound)) = grad_clip {
+            assert!(bound >= 0., "gradient clipping bound must be non-negative, got {}", bound);
         }
-        NN { layers, learning_rate }
+        self.grad_clip = grad_clip;
+    }
+
+    /// Penalties added to the summed squared error of every mini-batch during `train`.
+    pub fn set_regularizers(&mut self, regularizers: Vec<Regularizer>) {
+        self.regularizers = regularizers;
+    }
+
+    /// Total penalty of the regularizers for the current parameters, as a `(1, 1)` matrix.
+    pub fn penalty(&self) -> Result<Matrix, MatrixError> {
+        self.regularizers
+            .iter()
+            .try_fold(Matrix::zeros((1, 1), false), |sum, regularizer| sum.add(&regularizer.apply(self.parameters())?))
+    }
+
+    /// When enabled, the forward pass only keeps the input and output of every layer
+    /// in the computation graph and recomputes the rest during the backward pass.
+    pub fn set_checkpointing(&mut self, checkpointing: bool) {
+        self.model.set_checkpointing(checkpointing);
+    }
+
+    /// Splits every mini-batch of `train` across `threads` worker threads. Each worker
+    /// runs the forward and backward pass on its share of the samples, and the summed
+    /// gradients are applied in a single update. Values below 1 are treated as 1.
+    ///
+    /// Models with buffers, such as the running statistics of batch normalization, are
+    /// always trained on a single thread: every shard would normalize with its own batch
+    /// statistics and update the buffers once per shard.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Number of samples `predict` passes through the model at once, 256 by default.
+    /// Values below 1 are treated as 1.
+    pub fn set_predict_batch_size(&mut self, batch_size: usize) {
+        self.predict_batch_size = batch_size.max(1);
+    }
+
+    /// Returns the parameters of all layers, in order.
+    pub fn parameters(&self) -> impl Iterator<Item = &Parameter> {
+        self.model.parameters().into_iter()
+    }
+
+    pub fn parameters_mut(&mut self) -> impl Iterator<Item = &mut Parameter> {
+        self.model.parameters_mut().into_iter()
+    }
+
+    /// Returns the name and current value of every parameter, in order.
+    pub fn named_parameters(&self) -> impl Iterator<Item = (&str, &Matrix)> {
+        self.parameters().map(|param| (param.name(), param.value()))
+    }
+
+    /// Returns the parameter called `name`, if any.
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters().find(|param| param.name() == name)
+    }
+
+    pub fn summary(&self) -> String {
+        self.model.summary()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        self.model.save(path)
+    }
+
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        self.model.load(path)
     }
 
     pub fn forward(&self, xs: Matrix) -> Result<Matrix, Box<dyn Error>>{
+        Ok(self.model.forward(&xs)?)
+    }
 
-        let mut ys = xs;
-        for layer in self.layers.iter() {
-            ys = layer.act_func.apply(layer.w.matmul(&ys)?.add(&layer.b)?);
-            
+    /// Outputs of the model for samples given one per row, as a `(samples, outputs)` matrix
+    /// without history. Unlike `forward`, which takes a column per sample, the samples are
+    /// passed through the model in batches of at most `set_predict_batch_size` samples.
+    pub fn predict<I: PredictInput + ?Sized>(&self, input: &I) -> Result<Matrix, Box<dyn Error>> {
+        let mut xs = input.to_rows()?;
+        let (samples, features) = xs.shape();
+        if let Some(expected) = self.model.input_features() {
+            if samples == 0 {
+                // an empty list of rows does not know its number of features
+                xs = Matrix::zeros((0, expected), false);
+            } else if features != expected {
+                return Err(Box::new(NNError::FeatureCountMismatch { expected, got: features }));
+            }
+        }
+        if samples == 0 {
+            // a batch without samples still gives the number of outputs
+            return Ok(self.model.forward(&xs.t())?.t().detach());
         }
 
-        Ok(ys)
+        let outputs = (0..samples)
+            .step_by(self.predict_batch_size)
+            .map(|start| {
+                let batch = xs.slice_rows(start, (start + self.predict_batch_size).min(samples))?;
+                Ok(self.model.forward(&batch.t())?.t().detach())
+            })
+            .collect::<Result<Vec<Matrix>, MatrixError>>()?;
+        Ok(Matrix::concat(&outputs.iter().collect::<Vec<&Matrix>>(), 0)?)
     }
 
+    /// Class probabilities as a `(samples, classes)` matrix. A single output must be in
+    /// `[0, 1]` and is taken to be the probability of class 1, giving the two columns `1 - p`
+    /// and `p`. Several outputs must be non-negative, such as sigmoid outputs, and are divided
+    /// by the sum of their row. Raw scores are not normalized here, use
+    /// `predict(..)?.softmax(1)` for them instead.
+    pub fn predict_proba<I: PredictInput + ?Sized>(&self, input: &I) -> Result<Matrix, Box<dyn Error>> {
+        let ys = self.predict(input)?;
+        let (samples, outputs) = ys.shape();
+        if outputs == 1 {
+            if let Some((sample, &value)) = ys.data().iter().enumerate().find(|(_, y)| !(0. ..=1.).contains(*y)) {
+                return Err(Box::new(NNError::ProbabilityOutOfRange { sample, value }));
+            }
+            let complement = ys.mul_scalar(-1.).add_scalar(1.);
+            return Ok(Matrix::concat(&[&complement, &ys], 1)?);
+        }
+        if let Some((i, &value)) = ys.data().iter().enumerate().find(|(_, &y)| y < 0.) {
+            return Err(Box::new(NNError::NegativeOutput { sample: i / outputs, value }));
+        }
+
+        // a row of zeros carries no preference and is spread evenly over the classes
+        let data = ys.data()
+            .chunks(outputs.max(1))
+            .flat_map(|row| {
+                let sum = row.iter().sum::<f32>();
+                row.iter().map(move |&y| if sum > 0. { y / sum } else { 1. / outputs as f32 })
+            })
+            .collect();
+        Ok(Matrix::from_vec(data, (samples, outputs), false))
+    }
+
+    /// Most likely class of every sample: for a single output 1 if it is at least 0.5,
+    /// otherwise the index of the largest output.
+    pub fn predict_classes<I: PredictInput + ?Sized>(&self, input: &I) -> Result<Vec<usize>, Box<dyn Error>> {
+        let ys = self.predict(input)?;
+        if ys.shape().1 == 1 {
+            return Ok(ys.data().iter().map(|&p| usize::from(p >= 0.5)).collect());
+        }
+        Ok(ys.argmax(1)?)
+    }
+
+    /// Runs `epochs` steps of gradient descent on mini-batches of `batch_size` samples
+    /// and returns the mean squared error of every step.
     pub fn train(&mut self, 
-        x_train: &Vec<Vec<f32>>, 
-        y_train: &Vec<f32>, 
+        x_train: &[Vec<f32>], 
+        y_train: &[f32], 
         batch_size: usize, 
         epochs: usize) 
         -> Result<Vec<f32>, Box<dyn Error>> {
+        Ok(self.train_with_history(x_train, y_train, batch_size, epochs)?.loss)
+    }
+
+    /// Same as `train`, additionally reporting the penalty of the regularizers of every step.
+    pub fn train_with_history(&mut self, 
+        x_train: &[Vec<f32>], 
+        y_train: &[f32], 
+        batch_size: usize, 
+        epochs: usize) 
+        -> Result<TrainingHistory, Box<dyn Error>> {
         
         if x_train.len() != y_train.len() {
             return Err(Box::new(NNError::TrainDataMismatch { 
@@ -80,8 +278,23 @@ impl NN {
             }));
         }
 
+        // layers such as dropout behave differently while fitting
+        self.model.set_training(true);
+        let history = self.train_steps(x_train, y_train, batch_size, epochs);
+        self.model.set_training(false);
+        history
+    }
+
+    fn train_steps(&mut self, 
+        x_train: &[Vec<f32>], 
+        y_train: &[f32], 
+        batch_size: usize, 
+        epochs: usize) 
+        -> Result<TrainingHistory, Box<dyn Error>> {
+
+        let training_size = y_train.len();
         let mut rng = rand::thread_rng();
-        let mut history = vec![];
+        let mut history = TrainingHistory::default();
 
         let mut counter: usize = 0;
 
@@ -96,32 +309,86 @@ impl NN {
                 (0..training_size)
                     .collect::<Vec<usize>>()
                     .choose_multiple(&mut rng, batch_size)
-                    .into_iter()
                     .map(|i| (x_train[*i].clone(), y_train[*i]))
                     .unzip()
             };
 
-            let batch_x: Matrix = batch_x.into();
-            let batch_y: Matrix = batch_y.into();
-            let ys_pred = self.forward(batch_x.t())?.t();
+            let (mut grads, loss, penalty) = if self.threads > 1 && self.model.buffers().is_empty() {
+                self.parallel_grads(&batch_x, &batch_y)?
+            } else {
+                self.batch_grads(&batch_x, &batch_y, true)?
+            };
 
-            let loss = ys_pred.sub(&batch_y)?.powf(2.);
-    
-            let grads = loss.backward()?;
-    
-            for layer in self.layers.iter_mut() {
-                let dw = grads.get(layer.w.id()).unwrap();
-                let db = grads.get(layer.b.id()).unwrap();
+            let params = self.parameters().map(|param| param.value()).collect::<Vec<&Matrix>>();
+            match self.grad_clip {
+                Some(GradClip::Value(value)) => grads.clip_value(&params, -value, value),
+                Some(GradClip::Norm(max_norm)) => { grads.clip_norm(&params, max_norm); },
+                None => ()
+            }
     
-                layer.w = layer.w.sub(&dw.mul_scalar(self.learning_rate))?.no_history();
-                layer.b = layer.b.sub(&db.mul_scalar(self.learning_rate))?.no_history();
+            let learning_rate = self.learning_rate;
+            for param in self.parameters_mut() {
+                // tables such as those of embeddings only get a gradient for the rows in the batch
+                if let Some(rows) = grads.get_rows(param.value().id()) {
+                    param.descend_rows(rows, learning_rate)?;
+                    continue;
+                }
+                let grad = grads.get_param(param).unwrap();
+                let value = param.value().sub(&grad.mul_scalar(learning_rate))?.no_history();
+                param.set(value);
             }
     
-            let loss = loss.sum(1)?.get(0, 0);
-            history.push(loss/batch_size as f32);
+            history.loss.push(loss/batch_size as f32);
+            history.penalty.push(penalty);
 
         }
 
         Ok(history)
     }
+
+    // gradients of the summed squared error over the samples, plus the penalty if requested,
+    // together with the loss and the penalty themselves
+    fn batch_grads(&self, batch_x: &[Vec<f32>], batch_y: &[f32], with_penalty: bool) -> Result<(GradMap, f32, f32), MatrixError> {
+        let batch_x: Matrix = batch_x.to_vec().into();
+        let batch_y: Matrix = batch_y.to_vec().into();
+        let ys_pred = self.model.forward(&batch_x.t())?.t();
+
+        let loss = ys_pred.sub(&batch_y)?.powf(2.).sum_all()?;
+
+        if !with_penalty || self.regularizers.is_empty() {
+            let grads = loss.backward()?;
+            return Ok((grads, loss.get(0, 0), 0.));
+        }
+        let penalty = self.penalty()?;
+        let grads = loss.add(&penalty)?.backward()?;
+        Ok((grads, loss.get(0, 0), penalty.get(0, 0)))
+    }
+
+    // the loss is a sum over the samples, so are the gradients of the shards;
+    // the penalty is part of the first shard only
+    fn parallel_grads(&self, batch_x: &[Vec<f32>], batch_y: &[f32]) -> Result<(GradMap, f32, f32), MatrixError> {
+        let shard_size = batch_y.len().div_ceil(self.threads);
+
+        let shards = thread::scope(|s| {
+            let workers = batch_x
+                .chunks(shard_size)
+                .zip(batch_y.chunks(shard_size))
+                .enumerate()
+                .map(|(n, (x, y))| s.spawn(move || self.batch_grads(x, y, n == 0)))
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("training worker panicked"))
+                .collect::<Result<Vec<(GradMap, f32, f32)>, MatrixError>>()
+        })?;
+
+        let mut grads = GradMap::new();
+        let (mut loss, mut penalty) = (0., 0.);
+        for (shard_grads, shard_loss, shard_penalty) in shards.into_iter() {
+            grads.merge(shard_grads)?;
+            loss += shard_loss;
+            penalty += shard_penalty;
+        }
+        Ok((grads, loss, penalty))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,171 @@
+use std::sync::Mutex;
+
+use crate::{Matrix, Module, Parameter, error::MatrixError};
+
+const EPS: f32 = 1e-5;
+
+// (xs - mean) / sqrt(var + eps), scaled by weight and shifted by bias
+fn normalize(xs: &Matrix, mean: &Matrix, var: &Matrix, weight: &Parameter, bias: &Parameter) -> Result<Matrix, MatrixError> {
+    let x_hat = xs.sub(mean)?.div(&var.add_scalar(EPS).sqrt())?;
+    x_hat.mul(weight.value())?.add(bias.value())
+}
+
+fn affine(features: usize) -> (Parameter, Parameter) {
+    (
+        Parameter::new("weight", Matrix::ones((features, 1), true)),
+        Parameter::new("bias", Matrix::zeros((features, 1), true))
+    )
+}
+
+/// Batch normalization: normalizes every feature over the samples of the batch,
+/// followed by a learned scale (`weight`) and shift (`bias`) per feature.
+///
+/// In training mode the statistics of the batch are used and the running mean and
+/// variance are updated as `running = (1 - momentum) * running + momentum * batch`.
+/// In evaluation mode the running statistics are used instead. Both are buffers,
+/// which are saved and loaded together with the parameters.
+#[derive(Debug)]
+pub struct BatchNorm {
+    weight: Parameter,
+    bias: Parameter,
+    // updated in forward, which only borrows the layer
+    running_mean: Mutex<Parameter>,
+    running_var: Mutex<Parameter>,
+    momentum: f32,
+    training: bool
+}
+
+impl BatchNorm {
+    /// A new batch normalization layer is in training mode with a momentum of 0.1.
+    pub fn new(features: usize) -> Self {
+        let (weight, bias) = affine(features);
+        Self {
+            weight,
+            bias,
+            running_mean: Mutex::new(Parameter::new("running_mean", Matrix::zeros((features, 1), false))),
+            running_var: Mutex::new(Parameter::new("running_var", Matrix::ones((features, 1), false))),
+            momentum: 0.1,
+            training: true
+        }
+    }
+
+    pub fn set_momentum(&mut self, momentum: f32) {
+        self.momentum = momentum;
+    }
+
+    pub fn running_mean(&self) -> Matrix {
+        self.running_mean.lock().unwrap().value().clone()
+    }
+
+    pub fn running_var(&self) -> Matrix {
+        self.running_var.lock().unwrap().value().clone()
+    }
+
+    fn update(running: &Mutex<Parameter>, batch: &Matrix, momentum: f32) -> Result<(), MatrixError> {
+        let mut running = running.lock().unwrap();
+        let value = running.value()
+            .mul_scalar(1. - momentum)
+            .add(&batch.detach().mul_scalar(momentum))?
+            .no_history();
+        running.set(value);
+        Ok(())
+    }
+}
+
+impl Clone for BatchNorm {
+    fn clone(&self) -> Self {
+        Self {
+            weight: self.weight.clone(),
+            bias: self.bias.clone(),
+            running_mean: Mutex::new(self.running_mean.lock().unwrap().clone()),
+            running_var: Mutex::new(self.running_var.lock().unwrap().clone()),
+            momentum: self.momentum,
+            training: self.training
+        }
+    }
+}
+
+impl Module for BatchNorm {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        if !self.training {
+            return normalize(xs, &self.running_mean(), &self.running_var(), &self.weight, &self.bias);
+        }
+
+        let mean = xs.mean(1)?;
+        let var = xs.var(1)?;
+
+        // the running variance is an unbiased estimate
+        let samples = xs.shape().1;
+        let unbiased = if samples > 1 { var.mul_scalar(samples as f32 / (samples - 1) as f32) } else { var.clone() };
+        Self::update(&self.running_mean, &mean, self.momentum)?;
+        Self::update(&self.running_var, &unbiased, self.momentum)?;
+
+        normalize(xs, &mean, &var, &self.weight, &self.bias)
+    }
+
+    fn describe(&self) -> String {
+        format!("BatchNorm({})", self.weight.value().shape().0)
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.weight.value().shape().0)
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        vec![&self.weight, &self.bias]
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weight, &mut self.bias]
+    }
+
+    fn buffers(&self) -> Vec<Parameter> {
+        vec![self.running_mean.lock().unwrap().clone(), self.running_var.lock().unwrap().clone()]
+    }
+
+    fn buffers_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![self.running_mean.get_mut().unwrap(), self.running_var.get_mut().unwrap()]
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+/// Layer normalization: normalizes every sample over its features, followed by a
+/// learned scale (`weight`) and shift (`bias`) per feature. Behaves the same
+/// in training and evaluation mode.
+#[derive(Debug, Clone)]
+pub struct LayerNorm {
+    weight: Parameter,
+    bias: Parameter
+}
+
+impl LayerNorm {
+    pub fn new(features: usize) -> Self {
+        let (weight, bias) = affine(features);
+        Self { weight, bias }
+    }
+}
+
+impl Module for LayerNorm {
+    fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        normalize(xs, &xs.mean(0)?, &xs.var(0)?, &self.weight, &self.bias)
+    }
+
+    fn describe(&self) -> String {
+        format!("LayerNorm({})", self.weight.value().shape().0)
+    }
+
+    fn input_features(&self) -> Option<usize> {
+        Some(self.weight.value().shape().0)
+    }
+
+    fn parameters(&self) -> Vec<&Parameter> {
+        vec![&self.weight, &self.bias]
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weight, &mut self.bias]
+    }
+}
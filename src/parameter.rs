@@ -0,0 +1,59 @@
+use crate::{Float, GradMap, Matrix, RowGrad, error::MatrixError};
+
+/// A trainable matrix with a stable name, e.g. `layers.0.weight`.
+/// 
+/// Every update of a parameter replaces its value with a new matrix, and thereby a new id.
+/// The name stays the same and can be used to key state that has to survive updates,
+/// while the handle itself always resolves to the current value.
+#[derive(Debug, Clone)]
+pub struct Parameter<T = f32> {
+    name: String,
+    value: Matrix<T>
+}
+
+impl<T: Float> Parameter<T> {
+    pub fn new<S: Into<String>>(name: S, value: Matrix<T>) -> Self {
+        Self { name: name.into(), value }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &Matrix<T> {
+        &self.value
+    }
+
+    /// Prepends `prefix` to the name, e.g. `weight` becomes `0.weight` for prefix `0`.
+    pub fn add_prefix(&mut self, prefix: &str) {
+        self.name = format!("{}.{}", prefix, self.name);
+    }
+
+    /// Replaces the value of the parameter, keeping its name.
+    pub fn set(&mut self, value: Matrix<T>) {
+        self.value = value;
+    }
+
+    /// Gradient descent step on the rows held by the row-sparse `grad` only, which updates
+    /// them in place unless the current value is shared, e.g. by a clone of the model.
+    pub(crate) fn descend_rows(&mut self, grad: &RowGrad<T>, learning_rate: T) -> Result<(), MatrixError> {
+        if grad.shape() != self.value.shape() {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: self.value.shape(),
+                b_shape: grad.shape(),
+                op: "descend_rows".to_string()
+            });
+        }
+        // give up the current value, such that its data is not shared by this parameter
+        let value = std::mem::replace(&mut self.value, Matrix::zeros((0, 0), false));
+        self.value = value.sub_rows(grad, learning_rate);
+        Ok(())
+    }
+}
+
+impl<T: Float> GradMap<T> {
+    /// Gradient of the current value of `param`.
+    pub fn get_param(&self, param: &Parameter<T>) -> Option<&Matrix<T>> {
+        self.get(param.value().id())
+    }
+}
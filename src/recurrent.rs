@@ -0,0 +1,274 @@
+use crate::{Matrix, Module, Parameter, error::MatrixError};
+
+// A sequence is a slice of steps, each a batch of shape (inputs, samples). As a `Module`,
+// a recurrent layer takes the steps stacked along the rows, i.e. (steps * inputs, samples),
+// which is how a flattened sequence of feature vectors per sample arrives from `NN::train`.
+
+/// Which hidden states a recurrent layer returns from `Module::forward`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecurrentOutput {
+    /// The hidden state after the last step, of shape `(hidden, samples)`.
+    Last,
+    /// The hidden states of all steps stacked along the rows, of shape `(steps * hidden, samples)`.
+    All
+}
+
+// one step of a recurrent layer; the hidden state is the first matrix of the state
+trait Cell {
+    fn inputs(&self) -> usize;
+
+    fn hidden(&self) -> usize;
+
+    // the number of matrices making up the state, e.g. hidden and cell state
+    fn state_size(&self) -> usize {
+        1
+    }
+
+    fn step(&self, xs: &Matrix, state: &[Matrix]) -> Result<Vec<Matrix>, MatrixError>;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Unroll {
+    output: RecurrentOutput,
+    truncation: Option<usize>
+}
+
+impl Default for Unroll {
+    fn default() -> Self {
+        Self { output: RecurrentOutput::Last, truncation: None }
+    }
+}
+
+impl Unroll {
+    // the hidden states of all steps, starting from a zero state
+    fn run(&self, cell: &dyn Cell, inputs: &[Matrix]) -> Result<Vec<Matrix>, MatrixError> {
+        let Some(first) = inputs.first() else {
+            return Ok(vec![]);
+        };
+        let samples = first.shape().1;
+        let mut state = vec![Matrix::zeros((cell.hidden(), samples), false); cell.state_size()];
+
+        let mut hidden = Vec::with_capacity(inputs.len());
+        for (t, xs) in inputs.iter().enumerate() {
+            if xs.shape() != (cell.inputs(), samples) {
+                return Err(MatrixError::ShapeMismatchError {
+                    a_shape: (cell.inputs(), samples),
+                    b_shape: xs.shape(),
+                    op: "recurrent step".to_string()
+                });
+            }
+            // truncated backpropagation through time: no gradient flows past every k-th step
+            if matches!(self.truncation, Some(k) if t > 0 && t % k == 0) {
+                state = state.iter().map(Matrix::detach).collect();
+            }
+            state = cell.step(xs, &state)?;
+            hidden.push(state[0].clone());
+        }
+        Ok(hidden)
+    }
+
+    fn forward(&self, cell: &dyn Cell, xs: &Matrix) -> Result<Matrix, MatrixError> {
+        let (rows, samples) = xs.shape();
+        if rows == 0 || rows % cell.inputs() != 0 {
+            return Err(MatrixError::ShapeMismatchError {
+                a_shape: (cell.inputs(), samples),
+                b_shape: xs.shape(),
+                op: "recurrent".to_string()
+            });
+        }
+        let steps = xs.split(0, &vec![cell.inputs(); rows / cell.inputs()])?;
+        let hidden = self.run(cell, &steps)?;
+
+        match self.output {
+            RecurrentOutput::Last => Ok(hidden[hidden.len() - 1].clone()),
+            RecurrentOutput::All => Matrix::concat(&hidden.iter().collect::<Vec<&Matrix>>(), 0),
+        }
+    }
+}
+
+// input and recurrent weights and a bias for `gates` stacked gates, uniform in [-1/sqrt(hidden), 1/sqrt(hidden)]
+fn gate_parameters(inputs: usize, hidden: usize, gates: usize) -> (Parameter, Parameter, Parameter) {
+    let bound = 1. / (hidden as f32).sqrt();
+    (
+        Parameter::new("weight_ih", Matrix::randn(-bound, bound, (gates * hidden, inputs), true)),
+        Parameter::new("weight_hh", Matrix::randn(-bound, bound, (gates * hidden, hidden), true)),
+        Parameter::new("bias", Matrix::randn(-bound, bound, (gates * hidden, 1), true))
+    )
+}
+
+macro_rules! recurrent_layer {
+    ($name: ident) => {
+        impl $name {
+            /// Returns the hidden states of all steps instead of only the last one.
+            pub fn with_all_states(mut self) -> Self {
+                self.unroll.output = RecurrentOutput::All;
+                self
+            }
+
+            /// Truncated backpropagation through time: the state is detached every `steps`
+            /// steps, such that gradients flow back at most `steps` steps. Panics if `steps` is 0.
+            pub fn with_truncation(mut self, steps: usize) -> Self {
+                assert!(steps > 0, "truncation must be at least one step");
+                self.unroll.truncation = Some(steps);
+                self
+            }
+
+            /// Unrolls the layer over `inputs`, one batch of shape `(inputs, samples)` per step,
+            /// starting from a zero state. Returns the hidden state of every step.
+            pub fn forward_sequence(&self, inputs: &[Matrix]) -> Result<Vec<Matrix>, MatrixError> {
+                self.unroll.run(self, inputs)
+            }
+        }
+
+        impl Module for $name {
+            fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+                self.unroll.forward(self, xs)
+            }
+
+            fn describe(&self) -> String {
+                format!("{}({} -> {}, {:?})", stringify!($name), self.inputs(), self.hidden(), self.unroll.output)
+            }
+
+            fn parameters(&self) -> Vec<&Parameter> {
+                vec![&self.weight_ih, &self.weight_hh, &self.bias]
+            }
+
+            fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+                vec![&mut self.weight_ih, &mut self.weight_hh, &mut self.bias]
+            }
+        }
+    };
+}
+
+/// Elman recurrent layer computing `h = tanh(weight_ih @ x + weight_hh @ h + bias)` at every step.
+#[derive(Debug, Clone)]
+pub struct Rnn {
+    weight_ih: Parameter,
+    weight_hh: Parameter,
+    bias: Parameter,
+    unroll: Unroll
+}
+
+impl Rnn {
+    pub fn new(inputs: usize, hidden: usize) -> Self {
+        let (weight_ih, weight_hh, bias) = gate_parameters(inputs, hidden, 1);
+        Self { weight_ih, weight_hh, bias, unroll: Unroll::default() }
+    }
+}
+
+impl Cell for Rnn {
+    fn inputs(&self) -> usize {
+        self.weight_ih.value().shape().1
+    }
+
+    fn hidden(&self) -> usize {
+        self.weight_hh.value().shape().1
+    }
+
+    fn step(&self, xs: &Matrix, state: &[Matrix]) -> Result<Vec<Matrix>, MatrixError> {
+        let h = self.weight_ih.value().matmul(xs)?
+            .add(&self.weight_hh.value().matmul(&state[0])?)?
+            .add(self.bias.value())?
+            .tanh();
+        Ok(vec![h])
+    }
+}
+
+recurrent_layer!(Rnn);
+
+/// Long short-term memory layer. The weights and bias hold the input, forget, cell
+/// and output gates stacked along the rows, in this order:
+///
+/// `c = f * c + i * g` and `h = o * tanh(c)`, where `i`, `f` and `o` are sigmoid gates
+/// and `g = tanh(..)` is the candidate cell state.
+#[derive(Debug, Clone)]
+pub struct Lstm {
+    weight_ih: Parameter,
+    weight_hh: Parameter,
+    bias: Parameter,
+    unroll: Unroll
+}
+
+impl Lstm {
+    pub fn new(inputs: usize, hidden: usize) -> Self {
+        let (weight_ih, weight_hh, bias) = gate_parameters(inputs, hidden, 4);
+        Self { weight_ih, weight_hh, bias, unroll: Unroll::default() }
+    }
+}
+
+impl Cell for Lstm {
+    fn inputs(&self) -> usize {
+        self.weight_ih.value().shape().1
+    }
+
+    fn hidden(&self) -> usize {
+        self.weight_hh.value().shape().1
+    }
+
+    fn state_size(&self) -> usize {
+        2
+    }
+
+    fn step(&self, xs: &Matrix, state: &[Matrix]) -> Result<Vec<Matrix>, MatrixError> {
+        let (h, c) = (&state[0], &state[1]);
+        let gates = self.weight_ih.value().matmul(xs)?
+            .add(&self.weight_hh.value().matmul(h)?)?
+            .add(self.bias.value())?;
+
+        let hidden = self.hidden();
+        let gates = gates.split(0, &[hidden; 4])?;
+        let (i, f, g, o) = (gates[0].sigmoid(), gates[1].sigmoid(), gates[2].tanh(), gates[3].sigmoid());
+
+        let c = f.mul(c)?.add(&i.mul(&g)?)?;
+        let h = o.mul(&c.tanh())?;
+        Ok(vec![h, c])
+    }
+}
+
+recurrent_layer!(Lstm);
+
+/// Gated recurrent unit. The weights and bias hold the reset, update and candidate
+/// gates stacked along the rows, in this order:
+///
+/// `n = tanh(W_in x + r * (W_hn h) + b_n)` and `h = n + z * (h - n)`, where `r` and `z`
+/// are sigmoid gates.
+#[derive(Debug, Clone)]
+pub struct Gru {
+    weight_ih: Parameter,
+    weight_hh: Parameter,
+    bias: Parameter,
+    unroll: Unroll
+}
+
+impl Gru {
+    pub fn new(inputs: usize, hidden: usize) -> Self {
+        let (weight_ih, weight_hh, bias) = gate_parameters(inputs, hidden, 3);
+        Self { weight_ih, weight_hh, bias, unroll: Unroll::default() }
+    }
+}
+
+impl Cell for Gru {
+    fn inputs(&self) -> usize {
+        self.weight_ih.value().shape().1
+    }
+
+    fn hidden(&self) -> usize {
+        self.weight_hh.value().shape().1
+    }
+
+    fn step(&self, xs: &Matrix, state: &[Matrix]) -> Result<Vec<Matrix>, MatrixError> {
+        let h = &state[0];
+        let hidden = self.hidden();
+        let input = self.weight_ih.value().matmul(xs)?.add(self.bias.value())?.split(0, &[hidden; 3])?;
+        let recurrent = self.weight_hh.value().matmul(h)?.split(0, &[hidden; 3])?;
+
+        let r = input[0].add(&recurrent[0])?.sigmoid();
+        let z = input[1].add(&recurrent[1])?.sigmoid();
+        let n = input[2].add(&r.mul(&recurrent[2])?)?.tanh();
+
+        let h = n.add(&z.mul(&h.sub(&n)?)?)?;
+        Ok(vec![h])
+    }
+}
+
+recurrent_layer!(Gru);
@@ -0,0 +1,156 @@
+use crate::{Float, Matrix, Operator, UnaryOpType, anomaly, error::MatrixError};
+
+type MatrixResult<T> = Result<Matrix<T>, MatrixError>;
+
+// lanes of a matrix together with the shape of the reduced matrix
+type Lanes<T> = (Vec<Vec<T>>, (usize, usize));
+
+impl<T: Float> Matrix<T> {
+
+    // splits the data into the lanes that are reduced along axis, 
+    // i.e., the columns for axis 0 and the rows for axis 1
+    fn lanes(&self, axis: usize, op: &str) -> Result<Lanes<T>, MatrixError> {
+        let (rows, cols) = self.shape();
+        match axis {
+            0 => Ok((
+                (0..cols).map(|j| (0..rows).map(|i| self.get(i, j)).collect()).collect(), 
+                (1, cols)
+            )),
+            1 => Ok((
+                (0..rows).map(|i| (0..cols).map(|j| self.get(i, j)).collect()).collect(), 
+                (rows, 1)
+            )),
+            _ => Err(MatrixError::InvalidAxis { axis, op: op.to_string() })
+        }
+    }
+
+    fn extreme(&self, axis: usize, op_type: UnaryOpType<T>, op: &str, f: fn(T, T) -> T) -> MatrixResult<T> {
+        let (lanes, shape) = self.lanes(axis, op)?;
+        let data = lanes
+            .iter()
+            .map(|lane| lane.iter().copied().reduce(f).unwrap_or(T::NAN))
+            .collect();
+
+        let op = Operator::Unary(self.clone(), op_type);
+
+        let mat = Matrix::from_op(data, shape, op, self.requires_grad());
+        anomaly::raise(&mat)?;
+        Ok(mat)
+    }
+
+    fn arg_extreme(&self, axis: usize, op: &str, better: fn(T, T) -> bool) -> Result<Vec<usize>, MatrixError> {
+        let (lanes, _) = self.lanes(axis, op)?;
+        Ok(lanes
+            .iter()
+            .map(|lane| lane
+                .iter()
+                .enumerate()
+                .fold(0, |best, (i, &x)| if better(x, lane[best]) { i } else { best })
+            )
+            .collect())
+    }
+
+    // view of all elements as a single row, such that reducing along axis 1 reduces everything
+    fn flat(&self) -> MatrixResult<T> {
+        self.reshape((1, self.data().len()))
+    }
+
+    /// Number of elements reduced along `axis`.
+    fn axis_len(&self, axis: usize, op: &str) -> Result<usize, MatrixError> {
+        match axis {
+            0 => Ok(self.shape().0),
+            1 => Ok(self.shape().1),
+            _ => Err(MatrixError::InvalidAxis { axis, op: op.to_string() })
+        }
+    }
+
+    /// Sum of all elements as a `(1, 1)` matrix.
+    pub fn sum_all(&self) -> MatrixResult<T> {
+        self.flat()?.sum(1)
+    }
+
+    /// Arithmetic mean along `axis`, see `sum` for the axis convention.
+    pub fn mean(&self, axis: usize) -> MatrixResult<T> {
+        let n = self.axis_len(axis, "mean")?;
+        Ok(self.sum(axis)?.div_scalar(T::from_usize(n)))
+    }
+
+    pub fn mean_all(&self) -> MatrixResult<T> {
+        self.flat()?.mean(1)
+    }
+
+    /// Maximum along `axis`. When several elements are maximal, 
+    /// the gradient is divided evenly among them.
+    pub fn max(&self, axis: usize) -> MatrixResult<T> {
+        self.extreme(axis, UnaryOpType::Max, "max", T::max)
+    }
+
+    pub fn max_all(&self) -> MatrixResult<T> {
+        self.flat()?.max(1)
+    }
+
+    /// Minimum along `axis`. When several elements are minimal, 
+    /// the gradient is divided evenly among them.
+    pub fn min(&self, axis: usize) -> MatrixResult<T> {
+        self.extreme(axis, UnaryOpType::Min, "min", T::min)
+    }
+
+    pub fn min_all(&self) -> MatrixResult<T> {
+        self.flat()?.min(1)
+    }
+
+    /// Index of the first maximum of every column (axis 0) or row (axis 1).
+    pub fn argmax(&self, axis: usize) -> Result<Vec<usize>, MatrixError> {
+        self.arg_extreme(axis, "argmax", |x, best| x > best)
+    }
+
+    /// Row-major index of the first maximum over all elements.
+    pub fn argmax_all(&self) -> Result<usize, MatrixError> {
+        Ok(self.flat()?.argmax(1)?[0])
+    }
+
+    /// Index of the first minimum of every column (axis 0) or row (axis 1).
+    pub fn argmin(&self, axis: usize) -> Result<Vec<usize>, MatrixError> {
+        self.arg_extreme(axis, "argmin", |x, best| x < best)
+    }
+
+    /// Row-major index of the first minimum over all elements.
+    pub fn argmin_all(&self) -> Result<usize, MatrixError> {
+        Ok(self.flat()?.argmin(1)?[0])
+    }
+
+    /// Population variance along `axis`, i.e., the mean squared deviation from the mean.
+    pub fn var(&self, axis: usize) -> MatrixResult<T> {
+        let mean = self.mean(axis)?;
+        self.sub(&mean)?.powf(T::from_f64(2.)).mean(axis)
+    }
+
+    pub fn var_all(&self) -> MatrixResult<T> {
+        self.flat()?.var(1)
+    }
+
+    /// Population standard deviation along `axis`.
+    pub fn std(&self, axis: usize) -> MatrixResult<T> {
+        Ok(self.var(axis)?.sqrt())
+    }
+
+    pub fn std_all(&self) -> MatrixResult<T> {
+        self.flat()?.std(1)
+    }
+
+    /// Computes `ln(sum(exp(x)))` along `axis` without overflowing for large `x`.
+    pub fn logsumexp(&self, axis: usize) -> MatrixResult<T> {
+        // shifting by the maximum does not change the result nor the gradient
+        let max = self.max(axis)?.detach();
+        max.add(&self.sub(&max)?.exp().sum(axis)?.ln())
+    }
+
+    pub fn logsumexp_all(&self) -> MatrixResult<T> {
+        self.flat()?.logsumexp(1)
+    }
+
+    /// Normalizes `exp(x)` to sum to one along `axis`, e.g. axis 0 for a column per sample.
+    pub fn softmax(&self, axis: usize) -> MatrixResult<T> {
+        Ok(self.sub(&self.logsumexp(axis)?)?.exp())
+    }
+}
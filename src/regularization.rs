@@ -0,0 +1,73 @@
+use crate::{Matrix, Parameter, error::MatrixError};
+
+/// Penalty on the magnitude of a parameter, added to the loss during training.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penalty {
+    /// `l1 * sum(|w|)`, pushing weights to exactly zero.
+    L1(f32),
+    /// `l2 * sum(w^2)`, i.e. weight decay by `2 * l2 * learning_rate` per step.
+    L2(f32),
+    /// Sum of the L1 and the L2 penalty.
+    ElasticNet { l1: f32, l2: f32 }
+}
+
+impl Penalty {
+    /// The penalty of `value` as a `(1, 1)` matrix, which is part of the computation graph.
+    pub fn apply(&self, value: &Matrix) -> Result<Matrix, MatrixError> {
+        let l1 = |strength: f32| value.abs().sum_all().map(|sum| sum.mul_scalar(strength));
+        let l2 = |strength: f32| value.powf(2.).sum_all().map(|sum| sum.mul_scalar(strength));
+        match *self {
+            Penalty::L1(strength) => l1(strength),
+            Penalty::L2(strength) => l2(strength),
+            Penalty::ElasticNet { l1: a, l2: b } => l1(a)?.add(&l2(b)?)
+        }
+    }
+}
+
+/// Applies a `Penalty` to the parameters of a model, by default to all of them.
+#[derive(Debug, Clone)]
+pub struct Regularizer {
+    penalty: Penalty,
+    layer: Option<String>,
+    biases: bool
+}
+
+impl Regularizer {
+    pub fn new(penalty: Penalty) -> Self {
+        Self { penalty, layer: None, biases: true }
+    }
+
+    /// Only applies the penalty to the parameters of `layer`, i.e. those whose name
+    /// starts with `layer` followed by a dot, e.g. `layers.0` for `layers.0.weight`.
+    pub fn for_layer(mut self, layer: &str) -> Self {
+        self.layer = Some(layer.to_string());
+        self
+    }
+
+    /// Does not apply the penalty to parameters called `bias`.
+    pub fn excluding_biases(mut self) -> Self {
+        self.biases = false;
+        self
+    }
+
+    pub fn penalty(&self) -> Penalty {
+        self.penalty
+    }
+
+    pub fn applies_to(&self, param: &Parameter) -> bool {
+        let name = param.name();
+        let in_layer = self.layer
+            .as_ref()
+            .is_none_or(|layer| name.strip_prefix(layer.as_str()).is_some_and(|rest| rest.starts_with('.')));
+        let is_bias = name.rsplit('.').next() == Some("bias");
+        in_layer && (self.biases || !is_bias)
+    }
+
+    /// Sum of the penalties of the `params` this regularizer applies to, as a `(1, 1)` matrix.
+    pub fn apply<'a>(&self, params: impl IntoIterator<Item = &'a Parameter>) -> Result<Matrix, MatrixError> {
+        params
+            .into_iter()
+            .filter(|param| self.applies_to(param))
+            .try_fold(Matrix::zeros((1, 1), false), |sum, param| sum.add(&self.penalty.apply(param.value())?))
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::{Float, Matrix};
+
+/// Row-sparse gradient of a table of which only a few rows were looked up, such as the
+/// `weight` of an `Embedding`: the rows that received a gradient, in the order in which
+/// they were first reached, together with the gradient of each of them.
+#[derive(Debug, Clone)]
+pub struct RowGrad<T = f32> {
+    shape: (usize, usize),
+    rows: Vec<usize>,
+    // row-major, row k holds the gradient of row rows[k] of the table
+    values: Vec<T>,
+    positions: HashMap<usize, usize>
+}
+
+impl<T: Float> RowGrad<T> {
+
+    /// Gradient of a table of `shape` that received element `k` of `grad` at element `map[k]`
+    /// of its row-major data. All indices in `map` must be valid.
+    pub(crate) fn scatter(shape: (usize, usize), map: &[usize], grad: &Matrix<T>) -> Self {
+        let mut rows = Self { shape, rows: vec![], values: vec![], positions: HashMap::new() };
+        let cols = shape.1;
+        for (&k, &x) in map.iter().zip(grad.data().iter()) {
+            rows.row_mut(k / cols)[k % cols] += x;
+        }
+        rows
+    }
+
+    /// Shape of the table this is the gradient of.
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    pub fn rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// Gradients of `rows`, `shape().1` values each.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    // gradient of `row`, zero when the row is first reached
+    fn row_mut(&mut self, row: usize) -> &mut [T] {
+        let cols = self.shape.1;
+        let position = match self.positions.get(&row) {
+            Some(&position) => position,
+            None => {
+                self.rows.push(row);
+                self.values.extend((0..cols).map(|_| T::zero()));
+                self.positions.insert(row, self.rows.len() - 1);
+                self.rows.len() - 1
+            }
+        };
+        &mut self.values[position * cols..(position + 1) * cols]
+    }
+
+    /// Adds `other`, a gradient of the same table.
+    pub(crate) fn add(&mut self, other: &RowGrad<T>) {
+        let cols = self.shape.1;
+        for (&row, values) in other.rows.iter().zip(other.values.chunks(cols.max(1))) {
+            self.row_mut(row).iter_mut().zip(values).for_each(|(x, &y)| *x += y);
+        }
+    }
+
+    pub(crate) fn map_values<F: Fn(T) -> T>(&mut self, f: F) {
+        self.values.iter_mut().for_each(|x| *x = f(*x));
+    }
+
+    /// Adds this gradient to `dense`, a dense gradient of the same table.
+    pub(crate) fn add_to(&self, dense: &Matrix<T>) -> Matrix<T> {
+        let cols = self.shape.1;
+        let mut data = dense.data().clone();
+        for (&row, values) in self.rows.iter().zip(self.values.chunks(cols.max(1))) {
+            data[row * cols..(row + 1) * cols].iter_mut().zip(values).for_each(|(x, &y)| *x += y);
+        }
+        Matrix::from_vec(data, self.shape, dense.requires_grad())
+    }
+
+    /// The gradient as a dense matrix, with zeros in the rows that were not looked up.
+    pub fn to_dense(&self) -> Matrix<T> {
+        self.add_to(&Matrix::zeros(self.shape, false))
+    }
+}
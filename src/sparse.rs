@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use crate::{Float, Matrix, Operator, anomaly, error::MatrixError};
+
+#[derive(Debug)]
+struct SparseMatrix_<T> {
+    shape: (usize, usize),
+    // row i holds the entries indptr[i]..indptr[i + 1] of indices and values
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<T>
+}
+
+/// Sparse matrix in compressed sparse row (CSR) format. Sparse matrices are constants,
+/// but can be multiplied with a dense `Matrix` that is part of the computation graph.
+#[derive(Debug, Clone)]
+pub struct SparseMatrix<T = f32>(Arc<SparseMatrix_<T>>);
+
+impl<T: Float> SparseMatrix<T> {
+
+    /// Builds a sparse matrix from `(row, col, value)` triplets (COO format).
+    /// Values of duplicate positions are summed.
+    pub fn from_triplets(shape: (usize, usize), triplets: &[(usize, usize, T)]) -> Result<Self, MatrixError> {
+        let (rows, cols) = shape;
+        for &(i, j, _) in triplets.iter() {
+            if i >= rows {
+                return Err(MatrixError::IndexOutOfBounds { index: i, len: rows, op: "from_triplets".to_string() });
+            }
+            if j >= cols {
+                return Err(MatrixError::IndexOutOfBounds { index: j, len: cols, op: "from_triplets".to_string() });
+            }
+        }
+
+        let mut sorted = triplets.to_vec();
+        sorted.sort_by_key(|&(i, j, _)| (i, j));
+
+        let mut indptr = vec![0; rows + 1];
+        let mut indices: Vec<usize> = vec![];
+        let mut values: Vec<T> = vec![];
+        let mut last = None;
+        for (i, j, x) in sorted.into_iter() {
+            if last == Some((i, j)) {
+                *values.last_mut().unwrap() += x;
+                continue;
+            }
+            last = Some((i, j));
+            indptr[i + 1] += 1;
+            indices.push(j);
+            values.push(x);
+        }
+        for i in 0..rows {
+            indptr[i + 1] += indptr[i];
+        }
+
+        Ok(Self(Arc::new(SparseMatrix_ { shape, indptr, indices, values })))
+    }
+
+    /// Stores the non-zero elements of `mat`.
+    pub fn from_dense(mat: &Matrix<T>) -> Self {
+        let (rows, cols) = mat.shape();
+        let mut indptr = vec![0; rows + 1];
+        let mut indices = vec![];
+        let mut values = vec![];
+        for i in 0..rows {
+            for j in 0..cols {
+                let x = mat.get(i, j);
+                if x != T::zero() {
+                    indices.push(j);
+                    values.push(x);
+                }
+            }
+            indptr[i + 1] = indices.len();
+        }
+        Self(Arc::new(SparseMatrix_ { shape: (rows, cols), indptr, indices, values }))
+    }
+
+    pub fn to_dense(&self) -> Matrix<T> {
+        let (rows, cols) = self.shape();
+        let mut data = vec![T::zero(); rows * cols];
+        for (i, j, x) in self.triplets() {
+            data[i * cols + j] = x;
+        }
+        Matrix::from_vec(data, (rows, cols), false)
+    }
+
+    /// The stored entries as `(row, col, value)` triplets in row-major order.
+    pub fn triplets(&self) -> Vec<(usize, usize, T)> {
+        let sparse = &self.0;
+        (0..sparse.shape.0)
+            .flat_map(|i| (sparse.indptr[i]..sparse.indptr[i + 1])
+                .map(move |k| (i, sparse.indices[k], sparse.values[k]))
+            )
+            .collect()
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        self.0.shape
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.0.values.len()
+    }
+
+    pub fn t(&self) -> Self {
+        let (rows, cols) = self.shape();
+        let triplets = self.triplets()
+            .into_iter()
+            .map(|(i, j, x)| (j, i, x))
+            .collect::<Vec<(usize, usize, T)>>();
+        // indices are valid by construction
+        SparseMatrix::from_triplets((cols, rows), &triplets).unwrap()
+    }
+
+    fn product(&self, other: &Matrix<T>) -> Result<Vec<T>, MatrixError> {
+        let (a_rows, a_cols) = self.shape();
+        let (b_rows, b_cols) = other.shape();
+        if a_cols != b_rows {
+            return Err(MatrixError::ShapeMismatchError { 
+                a_shape: (a_rows, a_cols), 
+                b_shape: (b_rows, b_cols), 
+                op: "sparse matmul".to_string() 
+            });
+        }
+
+        // only the stored entries contribute to the product
+        let sparse = &self.0;
+        let mut data = vec![T::zero(); a_rows * b_cols];
+        for i in 0..a_rows {
+            for k in sparse.indptr[i]..sparse.indptr[i + 1] {
+                let (row, x) = (sparse.indices[k], sparse.values[k]);
+                for j in 0..b_cols {
+                    data[i * b_cols + j] += x * other.get(row, j);
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// Computes `self @ other`, recording the product in the graph of `other`.
+    pub fn matmul(&self, other: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        let data = self.product(other)?;
+        let shape = (self.shape().0, other.shape().1);
+        let op = Operator::SparseMatMul(self.clone(), other.clone());
+
+        let mat = Matrix::from_op(data, shape, op, other.requires_grad());
+        anomaly::raise(&mat)?;
+        Ok(mat)
+    }
+
+    /// Gradient of `self @ other` w.r.t. `other`, which is `self.T @ grad`.
+    pub(crate) fn matmul_grad(&self, grad: &Matrix<T>) -> Result<Matrix<T>, MatrixError> {
+        let t = self.t();
+        let data = t.product(grad)?;
+        Ok(Matrix::from_vec(data, (t.shape().0, grad.shape().1), false))
+    }
+}
+
+impl<T: Float> From<&Matrix<T>> for SparseMatrix<T> {
+    fn from(value: &Matrix<T>) -> Self {
+        SparseMatrix::from_dense(value)
+    }
+}
+
+impl<T: Float> From<&SparseMatrix<T>> for Matrix<T> {
+    fn from(value: &SparseMatrix<T>) -> Self {
+        value.to_dense()
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    // anomaly detection is a global switch, so the tests in this file only ever enable it
+
+    #[test]
+    fn anomaly_in_fallible_forward_op() {
+        set_anomaly_detection(true);
+
+        let a = Matrix::from_vec(vec![0., 1.], (1, 2), true);
+        let b = Matrix::from_vec(vec![1., 1.], (1, 2), false);
+
+        let res = b.div(&a).map(|_| ());
+        match res {
+            Err(MatrixError::AnomalyError { op, pass, input_shapes, output_shape, op_chain, .. }) => {
+                assert_eq!(op, "Div");
+                assert_eq!(pass, Pass::Forward);
+                assert_eq!(input_shapes, vec![(1, 2), (1, 2)]);
+                assert_eq!(output_shape, (1, 2));
+                assert_eq!(op_chain.len(), 1);
+            },
+            res => panic!("expected anomaly error, got {:?}", res)
+        }
+    }
+
+    #[test]
+    fn anomaly_in_infallible_forward_op_is_deferred() -> Result<(), Box<dyn Error>> {
+        set_anomaly_detection(true);
+
+        let a = Matrix::from_vec(vec![0., 1.], (2, 1), true);
+        let w = Matrix::from_vec(vec![1., 0., 0., 1.], (2, 2), true);
+
+        let h = w.matmul(&a)?.sigmoid().mul_scalar(0.);
+        // 0^-1 = inf, but powf cannot return an error
+        let y = h.powf(-1.);
+
+        match y.sum(0) {
+            Err(MatrixError::AnomalyError { op, node_id, op_chain, .. }) => {
+                assert_eq!(op, "Powf32");
+                assert_eq!(node_id, y.id());
+                assert_eq!(op_chain, vec![
+                    format!("Powf32#{}", y.id()),
+                    format!("MulScalar#{}", h.id()),
+                    format!("Sigmoid#{}", h.op().as_ref().unwrap().operands()[0].id()),
+                    format!("MatMul#{}", h.op().as_ref().unwrap().operands()[0].op().as_ref().unwrap().operands()[0].id()),
+                ]);
+            },
+            res => panic!("expected anomaly error, got {:?}", res)
+        }
+
+        // the error stays with y and the results depending on it
+        assert!(y.sum(1).is_err());
+        assert!(a.sum(0).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn deferred_anomaly_is_not_reported_by_unrelated_ops() -> Result<(), Box<dyn Error>> {
+        set_anomaly_detection(true);
+
+        let a = Matrix::from_vec(vec![0., 1.], (1, 2), true);
+        let y = a.powf(-1.);
+
+        // an unrelated chain on the same thread runs normally
+        let b = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let c = b.add(&b)?.sum(1)?;
+        assert!(c.backward().is_ok());
+
+        // the anomaly is reported once y is used, even through other infallible ops
+        let z = y.mul_scalar(2.).sigmoid();
+        match z.backward() {
+            Err(MatrixError::AnomalyError { op, node_id, .. }) => {
+                assert_eq!(op, "Powf32");
+                assert_eq!(node_id, y.id());
+            },
+            res => panic!("expected anomaly error, got {:?}", res)
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn anomaly_in_backward() -> Result<(), Box<dyn Error>> {
+        set_anomaly_detection(true);
+
+        // sqrt(0) is finite, its derivative is not
+        let a = Matrix::from_vec(vec![0., 4.], (1, 2), true);
+        let b = a.powf(0.5);
+        let c = b.mul_scalar(2.);
+
+        match c.backward() {
+            Err(MatrixError::AnomalyError { op, node_id, pass, .. }) => {
+                assert_eq!(op, "Powf32");
+                assert_eq!(node_id, b.id());
+                assert_eq!(pass, Pass::Backward);
+            },
+            res => panic!("expected anomaly error, got {:?}", res)
+        }
+
+        let d = Matrix::from_vec(vec![1., 4.], (1, 2), true).powf(0.5);
+        assert!(d.backward().is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    // 3 tokens of 2 features as columns
+    fn qkv() -> [Matrix; 3] {
+        [
+            Matrix::from_vec(vec![0.5, -0.2, 1.0, 0.3, 0.1, -0.7], (2, 3), true),
+            Matrix::from_vec(vec![0.2, 0.9, -0.4, -0.1, 0.6, 0.3], (2, 3), true),
+            Matrix::from_vec(vec![1.0, 2.0, 3.0, -1.0, 0.0, 1.0], (2, 3), true),
+        ]
+    }
+
+    #[test]
+    fn scaled_dot_product() -> Result<(), Box<dyn Error>> {
+
+        let [q, k, v] = qkv();
+        let out = scaled_dot_product_attention(&q, &k, &v, None)?;
+        assert_eq!(out.shape(), (2, 3));
+
+        // query 0 by hand
+        let scores = (0..3)
+            .map(|i| (k.get(0, i) * q.get(0, 0) + k.get(1, i) * q.get(1, 0)) / 2_f32.sqrt())
+            .collect::<Vec<f32>>();
+        let total = scores.iter().map(|s| s.exp()).sum::<f32>();
+        let expected = (0..3).map(|i| scores[i].exp() / total * v.get(0, i)).sum::<f32>();
+        assert!((out.get(0, 0) - expected).abs() < 1e-6);
+
+        // with a causal mask, the first token only attends to itself
+        let out = scaled_dot_product_attention(&q, &k, &v, Some(&causal_mask(3)))?;
+        assert!((out.get(0, 0) - v.get(0, 0)).abs() < 1e-6);
+        assert!((out.get(1, 0) - v.get(1, 0)).abs() < 1e-6);
+
+        assert!(scaled_dot_product_attention(&q, &k, &v, Some(&causal_mask(2))).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn attention_gradients() -> Result<(), Box<dyn Error>> {
+
+        check_gradients(|xs| scaled_dot_product_attention(&xs[0], &xs[1], &xs[2], None), &qkv())?;
+        check_gradients(|xs| scaled_dot_product_attention(&xs[0], &xs[1], &xs[2], Some(&causal_mask(3))), &qkv())?;
+        Ok(())
+    }
+
+    // 3 samples of 4 tokens with 4 features
+    fn sequences() -> Matrix {
+        let data = (0..48).map(|i| ((i * 17) % 11) as f32 / 11. - 0.5).collect();
+        Matrix::from_vec(data, (16, 3), true)
+    }
+
+    #[test]
+    fn causal_multi_head_attention() -> Result<(), Box<dyn Error>> {
+
+        let attention = MultiHeadAttention::new(4, 2).with_causal_mask();
+        let xs = sequences();
+        let ys = attention.forward(&xs)?;
+        assert_eq!(ys.shape(), (16, 3));
+        assert_eq!(attention.num_parameters(), 4 * (16 + 4));
+
+        // changing the last token leaves the outputs of the earlier tokens unchanged
+        let mut data = xs.data().clone();
+        data[12 * 3..].iter_mut().for_each(|x| *x += 1.);
+        let changed = attention.forward(&Matrix::from_vec(data, (16, 3), false))?;
+        for k in 0..12 * 3 {
+            assert!((ys.data()[k] - changed.data()[k]).abs() < 1e-6);
+        }
+        assert!((12 * 3..16 * 3).any(|k| (ys.data()[k] - changed.data()[k]).abs() > 1e-3));
+
+        check_gradients(|xs| attention.forward(&xs[0]), &[sequences()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn positional_encodings() -> Result<(), Box<dyn Error>> {
+
+        let pe = positional_encoding(3, 4);
+        assert_eq!(pe.shape(), (4, 3));
+        assert_eq!(pe.get(0, 0), 0.);
+        assert_eq!(pe.get(1, 0), 1.);
+        assert!((pe.get(0, 2) - 2_f32.sin()).abs() < 1e-6);
+        assert!((pe.get(3, 1) - 0.01_f32.cos()).abs() < 1e-6);
+
+        // token t of every sample is shifted by column t
+        let ys = PositionalEncoding::new(4).forward(&Matrix::zeros((12, 2), false))?;
+        assert_eq!(ys.get(4 + 2, 1), pe.get(2, 1));
+        assert!(PositionalEncoding::new(4).forward(&Matrix::zeros((10, 2), false)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn encoder_block() -> Result<(), Box<dyn Error>> {
+
+        let mut block = TransformerEncoderBlock::new(4, 2, 8);
+        assert_eq!(block.forward(&sequences())?.shape(), (16, 3));
+        let names = block.parameters().iter().map(|param| param.name().to_string()).collect::<Vec<String>>();
+        assert_eq!(names.len(), 16);
+        assert_eq!(names[0], "attention.query.weight");
+        assert!(names.contains(&"norm_1.weight".to_string()));
+        assert_eq!(names[15], "norm_2.bias");
+
+        // fixed parameters, some random ones make the layer norms too curved for the finite differences
+        for (n, param) in block.parameters_mut().into_iter().enumerate() {
+            let shape = param.value().shape();
+            let data = (0..shape.0 * shape.1).map(|i| ((i * 7 + n * 3) % 11) as f32 / 22. - 0.25).collect();
+            param.set(Matrix::from_vec(data, shape, true));
+        }
+        // keeps the hidden units active, such that the finite differences do not cross the kink of relu
+        let bias = block.parameters_mut().into_iter().find(|param| param.name() == "linear_1.bias").unwrap();
+        bias.set(Matrix::fill((8, 1), 5., true));
+        check_gradients(|xs| block.forward(&xs[0]), &[sequences()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_classification() -> Result<(), Box<dyn Error>> {
+
+        // tokens are one-hot symbols a, b and c; the label is whether a occurs more often than b
+        let symbols = [[1., 0., 0., 0.], [0., 1., 0., 0.], [0., 0., 1., 0.]];
+        let (x, y): (Vec<Vec<f32>>, Vec<f32>) = (0..40)
+            .map(|i: usize| {
+                let sequence = (0..5).map(|t| (i * 7 + t * (i % 5 + 1) + t * t) % 3).collect::<Vec<usize>>();
+                let count = |s| sequence.iter().filter(|&&x| x == s).count();
+                let features = sequence.iter().flat_map(|&s| symbols[s]).collect();
+                (features, if count(0) > count(1) { 1. } else { 0. })
+            })
+            .unzip();
+
+        let model = Sequential::new()
+            .push(TransformerEncoderBlock::new(4, 2, 8))
+            .push(Dense::new(5 * 4, 1, Activation::Sigmoid));
+        let mut nn = NN::from_model(model, 0.05);
+        nn.train(&x, &y, 8, 300)?;
+
+        let xs: Matrix = x.clone().into();
+        let ys = nn.forward(xs.t())?;
+        let correct = y.iter().enumerate().filter(|(j, &label)| (ys.get(0, *j) > 0.5) == (label == 1.)).count();
+        assert!(correct >= 36, "{} of 40 correct", correct);
+        Ok(())
+    }
+}
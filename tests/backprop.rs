@@ -35,5 +35,19 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn backprop_shared_node() -> Result<(), Box<dyn Error>> {
+
+        // h is used twice, its gradient must only be propagated once: d(x^4)/dx = 4x^3
+        let x = Matrix::from_vec(vec![3.], (1, 1), true);
+        let h = x.mul(&x)?;
+        let y = h.mul(&h)?;
+
+        let grads = y.backward()?;
+        assert_eq!(grads.get(x.id()).unwrap().data(), &vec![108.]);
+
+        Ok(())
+    }
     
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    #[test]
+    fn sum_axis_convention() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 2., 3., 4., 5., 6.], (2, 3), false);
+
+        // axis 0 sums over the rows
+        let c = a.sum(0)?;
+        assert_eq!(c.shape(), (1, 3));
+        assert_eq!(c.data(), &vec![5., 7., 9.]);
+
+        // axis 1 sums over the columns
+        let c = a.sum(1)?;
+        assert_eq!(c.shape(), (2, 1));
+        assert_eq!(c.data(), &vec![6., 15.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn broadcast_all_patterns() -> Result<(), Box<dyn Error>> {
+        let scalar = Matrix::from_vec(vec![2.], (1, 1), false);
+        let c = scalar.broadcast_as((2, 3))?;
+        assert_eq!(c.data(), &vec![2.; 6]);
+
+        let row = Matrix::from_vec(vec![1., 2., 3.], (1, 3), false);
+        let c = row.broadcast_as((2, 3))?;
+        assert_eq!(c.data(), &vec![1., 2., 3., 1., 2., 3.]);
+
+        let col = Matrix::from_vec(vec![1., 2.], (2, 1), false);
+        let c = col.broadcast_as((2, 3))?;
+        assert_eq!(c.data(), &vec![1., 1., 1., 2., 2., 2.]);
+
+        assert!(col.broadcast_as((3, 3)).is_err());
+        assert!(row.broadcast_as((2, 2)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn broadcast_gradients_reduce_to_source_shape() -> Result<(), Box<dyn Error>> {
+        let shapes = [(1, 1), (1, 3), (2, 1), (2, 3)];
+
+        for &src in shapes.iter() {
+            let x = Matrix::randn(-1., 1., src, true);
+            let grads = x.broadcast_as((2, 3))?.backward()?;
+            assert_eq!(grads.get(x.id()).unwrap().shape(), src);
+
+            check_gradients(|xs| xs[0].broadcast_as((2, 3)), &[x])?;
+        }
+
+        for &lhs in shapes.iter() {
+            for &rhs in shapes.iter() {
+                let a = Matrix::randn(-1., 1., lhs, true);
+                let b = Matrix::randn(1., 2., rhs, true);
+
+                check_gradients(|xs| xs[0].add(&xs[1]), &[a.clone(), b.clone()])?;
+                check_gradients(|xs| xs[0].sub(&xs[1]), &[a.clone(), b.clone()])?;
+                check_gradients(|xs| xs[0].mul(&xs[1]), &[a.clone(), b.clone()])?;
+                check_gradients(|xs| xs[0].div(&xs[1]), &[a.clone(), b.clone()])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sum_and_transpose_gradients() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::randn(-1., 1., (2, 3), true);
+        let b = Matrix::randn(-1., 1., (2, 4), true);
+
+        check_gradients(|xs| xs[0].sum(0), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].sum(1), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].t().matmul(&xs[1]), &[a.clone(), b.clone()])?;
+
+        // a shared intermediate result must only propagate its gradient once
+        check_gradients(|xs| {
+            let h = xs[0].sigmoid();
+            h.mul(&h)
+        }, &[a])?;
+
+        Ok(())
+    }
+}
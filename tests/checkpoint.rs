@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+
+    use std::{error::Error, sync::{Arc, atomic::{AtomicUsize, Ordering}}};
+
+    use neural_network::*;
+
+    fn mlp(w1: &Matrix, w2: &Matrix, x: &Matrix) -> Result<Matrix, MatrixError> {
+        Ok(w2.matmul(&w1.matmul(x)?.sigmoid())?.sigmoid())
+    }
+
+    #[test]
+    fn checkpoint_gradients_match() -> Result<(), Box<dyn Error>> {
+        let w1 = Matrix::randn(-1., 1., (4, 3), true);
+        let w2 = Matrix::randn(-1., 1., (2, 4), true);
+        let x = Matrix::randn(-1., 1., (3, 5), true);
+
+        let expected = mlp(&w1, &w2, &x)?.powf(2.);
+        let expected_grads = expected.backward()?;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (w1_c, w2_c, calls_c) = (w1.clone(), w2.clone(), calls.clone());
+        let out = Matrix::checkpoint(&[&x], move |xs| {
+            calls_c.fetch_add(1, Ordering::Relaxed);
+            mlp(&w1_c, &w2_c, &xs[0])
+        })?.powf(2.);
+        
+        assert_eq!(out.data(), expected.data());
+        // only x, the checkpoint and powf remain in the graph, w1 and w2 are captured by the closure
+        assert_eq!(out.graph_nodes().len(), 3);
+        assert!(expected.graph_nodes().len() > out.graph_nodes().len());
+
+        let grads = out.backward()?;
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        for mat in [&x, &w1, &w2] {
+            let grad = grads.get(mat.id()).unwrap();
+            let expected_grad = expected_grads.get(mat.id()).unwrap();
+            for (a, b) in grad.data().iter().zip(expected_grad.data().iter()) {
+                assert!((a - b).abs() < 1e-6);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn checkpointed_training() -> Result<(), Box<dyn Error>> {
+        let x_train = vec![
+            vec![0., 0.],
+            vec![0., 1.],
+            vec![1., 0.],
+            vec![1., 1.],
+        ];
+        let y_train = vec![0., 1., 1., 0.];
+
+        // same initial parameters, batches of 1 are taken in order
+        let mut plain = NN::new(vec![2, 4, 1], 0.5);
+        let mut checkpointed = plain.clone();
+        checkpointed.set_checkpointing(true);
+
+        let expected = plain.train(&x_train, &y_train, 1, 20)?;
+        let losses = checkpointed.train(&x_train, &y_train, 1, 20)?;
+        assert_eq!(losses.len(), 20);
+        for (a, b) in losses.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        for ((name, a), (_, b)) in checkpointed.named_parameters().zip(plain.named_parameters()) {
+            for (a, b) in a.data().iter().zip(b.data().iter()) {
+                assert!((a - b).abs() < 1e-6, "{}", name);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn captured_non_leaf_has_no_gradient() -> Result<(), Box<dyn Error>> {
+        let w = Matrix::randn(-1., 1., (2, 3), true);
+        let x = Matrix::randn(-1., 1., (3, 4), true);
+        let h = w.sigmoid();
+
+        let expected_grads = h.matmul(&x)?.backward()?;
+
+        let h_c = h.clone();
+        let out = Matrix::checkpoint(&[&x], move |xs| h_c.matmul(&xs[0]))?;
+        let grads = out.backward()?;
+
+        // the gradient of h itself is dropped, the one of the leaf w behind it is not
+        assert!(grads.get(h.id()).is_none());
+        assert!(expected_grads.get(h.id()).is_some());
+        for mat in [&w, &x] {
+            let grad = grads.get(mat.id()).unwrap();
+            let expected_grad = expected_grads.get(mat.id()).unwrap();
+            for (a, b) in grad.data().iter().zip(expected_grad.data().iter()) {
+                assert!((a - b).abs() < 1e-6);
+            }
+        }
+
+        Ok(())
+    }
+}
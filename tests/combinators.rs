@@ -0,0 +1,108 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    fn batch() -> Matrix {
+        Matrix::from_vec(vec![0.5, -0.3, 1.2, 0.1, 0.8, -0.7], (2, 3), true)
+    }
+
+    fn names(module: &dyn Module) -> Vec<String> {
+        module.parameters().iter().map(|param| param.name().to_string()).collect()
+    }
+
+    #[test]
+    fn residual() -> Result<(), Box<dyn Error>> {
+
+        let dense = Dense::new(2, 2, Activation::Tanh);
+        let block = Residual::new(dense.clone());
+        assert_eq!(names(&block), vec!["body.weight", "body.bias"]);
+
+        let expected = dense.forward(&batch())?.add(&batch())?;
+        assert_eq!(block.forward(&batch())?.data(), expected.data());
+
+        // the body changes the number of features, which needs a projection
+        let block = Residual::new(Dense::new(2, 3, Activation::Tanh));
+        assert!(matches!(block.forward(&batch()), Err(MatrixError::ShapeMismatchError { .. })));
+        let block = block.with_shortcut(Dense::new(2, 3, Activation::None));
+        assert_eq!(names(&block), vec!["body.weight", "body.bias", "shortcut.weight", "shortcut.bias"]);
+        assert_eq!(block.forward(&batch())?.shape(), (3, 3));
+
+        check_gradients(|xs| block.forward(&xs[0]), &[batch()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn parallel() -> Result<(), Box<dyn Error>> {
+
+        let (a, b) = (Dense::new(2, 3, Activation::Tanh), Dense::new(2, 1, Activation::Sigmoid));
+        let concat = Parallel::new(Merge::Concat).push(a.clone()).push(b.clone());
+        assert_eq!(names(&concat), vec!["0.weight", "0.bias", "1.weight", "1.bias"]);
+
+        let ys = concat.forward(&batch())?;
+        assert_eq!(ys.shape(), (4, 3));
+        assert_eq!(ys.slice_rows(0, 3)?.data(), a.forward(&batch())?.data());
+        assert_eq!(ys.slice_rows(3, 4)?.data(), b.forward(&batch())?.data());
+
+        let add = Parallel::new(Merge::Add).push(a.clone()).push(Activation::Tanh);
+        assert!(add.forward(&batch()).is_err());
+        let add = Parallel::new(Merge::Add).push(Dense::new(2, 2, Activation::None)).push(Activation::Tanh);
+        assert_eq!(add.forward(&batch())?.shape(), (2, 3));
+
+        // the input is shared by both branches and the skip connection
+        check_gradients(|xs| concat.forward(&xs[0]), &[batch()])?;
+        check_gradients(|xs| add.forward(&xs[0]), &[batch()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn nested_names_and_buffers() -> Result<(), Box<dyn Error>> {
+
+        let mut model = Sequential::new()
+            .push(Residual::new(Sequential::new().push(Dense::new(2, 2, Activation::None)).push(BatchNorm::new(2))))
+            .push(Dense::new(2, 1, Activation::None));
+
+        let state = model.state_dict().into_iter().map(|(name, _)| name).collect::<Vec<String>>();
+        assert_eq!(state, vec![
+            "0.body.0.weight", "0.body.0.bias", "0.body.1.weight", "0.body.1.bias", "1.weight", "1.bias",
+            "0.body.1.running_mean", "0.body.1.running_var",
+        ]);
+
+        model.set_training(false);
+        let before = model.forward(&batch())?;
+        model.set_training(true);
+        model.forward(&batch())?;
+        model.set_training(false);
+        // the running statistics of the nested batch norm were updated
+        assert_ne!(model.forward(&batch())?.data(), before.data());
+        Ok(())
+    }
+
+    #[test]
+    fn wide_and_deep_is_trained() -> Result<(), Box<dyn Error>> {
+
+        // y = sigmoid of a linear part plus a nonlinear part
+        let x = (0..40)
+            .map(|i| vec![(i % 8) as f32 / 4. - 1., (i % 5) as f32 / 2. - 1.])
+            .collect::<Vec<Vec<f32>>>();
+        let y = x.iter().map(|x| 1. / (1. + (-(x[0] - x[1] + 2. * x[0] * x[1])).exp())).collect::<Vec<f32>>();
+
+        let deep = Sequential::new()
+            .push(Dense::new(2, 8, Activation::Tanh))
+            .push(Residual::new(Dense::new(8, 8, Activation::Tanh)));
+        let model = Sequential::new()
+            .push(Parallel::new(Merge::Concat).push(Activation::None).push(deep))
+            .push(Dense::new(10, 1, Activation::Sigmoid));
+
+        let mut nn = NN::from_model(model, 0.2);
+        let history = nn.train(&x, &y, 10, 500)?;
+        assert!(history.last().unwrap() < &(history[0] / 10.));
+        Ok(())
+    }
+}
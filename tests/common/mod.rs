@@ -0,0 +1,50 @@
+use neural_network::*;
+
+const EPS: f64 = 1e-2;
+const TOLERANCE: f64 = 1e-2;
+
+// weights the outputs differently, such that mistakes in the reduction of a gradient show up
+fn output_weights<T: Float>(shape: (usize, usize)) -> Matrix<T> {
+    let data = (0..shape.0 * shape.1)
+        .map(|i| T::from_f64(1. + 0.25 * i as f64))
+        .collect::<Vec<T>>();
+    Matrix::from_vec(data, shape, false)
+}
+
+fn weighted_sum<T: Float, F>(f: &F, inputs: &[Matrix<T>]) -> Result<Matrix<T>, MatrixError>
+where F: Fn(&[Matrix<T>]) -> Result<Matrix<T>, MatrixError>
+{
+    let out = f(inputs)?;
+    out.mul(&output_weights(out.shape()))?.sum(0)?.sum(1)
+}
+
+/// Compares the gradients computed by `backward` for every input of `f` 
+/// against central differences of a weighted sum of the outputs of `f`.
+pub fn check_gradients<T: Float, F>(f: F, inputs: &[Matrix<T>]) -> Result<(), MatrixError>
+where F: Fn(&[Matrix<T>]) -> Result<Matrix<T>, MatrixError>
+{
+    let grads = weighted_sum(&f, inputs)?.backward()?;
+
+    for (n, input) in inputs.iter().enumerate() {
+        let grad = grads.get_dense(input.id()).expect("input has no gradient");
+        assert_eq!(grad.shape(), input.shape(), "gradient shape of input {}", n);
+
+        for k in 0..input.data().len() {
+            let perturbed = |delta: f64| -> Result<f64, MatrixError> {
+                let mut data = input.data().clone();
+                data[k] += T::from_f64(delta);
+                let mut inputs = inputs.to_vec();
+                inputs[n] = Matrix::from_vec(data, input.shape(), true);
+                Ok(weighted_sum(&f, &inputs)?.get(0, 0).to_f64())
+            };
+            let numerical = (perturbed(EPS)? - perturbed(-EPS)?) / (2. * EPS);
+            let analytical = grad.data()[k].to_f64();
+
+            assert!(
+                (numerical - analytical).abs() <= TOLERANCE * analytical.abs().max(1.),
+                "gradient of input {} at element {}: numerical {} vs analytical {}", n, k, numerical, analytical
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    // distinct values, such that pooling windows have a unique maximum
+    fn images(channels: usize, (height, width): (usize, usize), samples: usize) -> Matrix {
+        let len = channels * height * width * samples;
+        let data = (0..len).map(|i| ((i * 7919) % len) as f32 / len as f32 - 0.5).collect();
+        Matrix::from_vec(data, (channels * height * width, samples), true)
+    }
+
+    // direct convolution of feature maps stored as columns
+    #[allow(clippy::too_many_arguments)]
+    fn naive_conv(
+        xs: &Matrix, weight: &Matrix, bias: &Matrix, channels: usize, (height, width): (usize, usize),
+        (kh, kw): (usize, usize), (sh, sw): (usize, usize), (ph, pw): (usize, usize), (dh, dw): (usize, usize)
+    ) -> Vec<f32> {
+        let out_channels = weight.shape().0;
+        let out_height = (height + 2 * ph - dh * (kh - 1) - 1) / sh + 1;
+        let out_width = (width + 2 * pw - dw * (kw - 1) - 1) / sw + 1;
+        let samples = xs.shape().1;
+
+        let mut out = vec![0.; out_channels * out_height * out_width * samples];
+        for o in 0..out_channels {
+            for oy in 0..out_height {
+                for ox in 0..out_width {
+                    for n in 0..samples {
+                        let mut acc = bias.get(o, 0);
+                        for c in 0..channels {
+                            for ki in 0..kh {
+                                for kj in 0..kw {
+                                    let y = (oy * sh + ki * dh) as isize - ph as isize;
+                                    let x = (ox * sw + kj * dw) as isize - pw as isize;
+                                    if y < 0 || x < 0 || y >= height as isize || x >= width as isize {
+                                        continue;
+                                    }
+                                    let row = (c * height + y as usize) * width + x as usize;
+                                    acc += weight.get(o, (c * kh + ki) * kw + kj) * xs.get(row, n);
+                                }
+                            }
+                        }
+                        out[((o * out_height + oy) * out_width + ox) * samples + n] = acc;
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn conv2d_matches_direct_convolution() -> Result<(), Box<dyn Error>> {
+
+        let xs = images(2, (5, 6), 3);
+        let conv = Conv2d::new(2, 3, (3, 2), (5, 6))
+            .with_stride((2, 2))
+            .with_padding((1, 1))
+            .with_dilation((2, 1));
+        assert_eq!(conv.output_shape(), (3, 2, 4));
+
+        let ys = conv.forward(&xs)?;
+        assert_eq!(ys.shape(), (3 * 2 * 4, 3));
+
+        // the dilated kernel spans 5 rows of the padded 7 x 8 input
+        let expected = naive_conv(&xs, conv.weight().value(), conv.bias().value(), 2, (5, 6), (3, 2), (2, 2), (1, 1), (2, 1));
+        for (a, b) in ys.data().iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn conv2d_gradients() -> Result<(), Box<dyn Error>> {
+
+        let conv = Conv2d::new(2, 2, (2, 2), (4, 3)).with_padding((1, 0)).with_dilation((1, 2));
+        let leaf = |mat: &Matrix| Matrix::from_vec(mat.data().to_vec(), mat.shape(), true);
+        let (weight, bias) = (leaf(conv.weight().value()), leaf(conv.bias().value()));
+
+        check_gradients(|xs| {
+            let mut conv = conv.clone();
+            let params = conv.parameters_mut();
+            let [w, b] = params.try_into().unwrap();
+            w.set(xs[1].clone());
+            b.set(xs[2].clone());
+            conv.forward(&xs[0])
+        }, &[images(2, (4, 3), 2), weight, bias])?;
+        Ok(())
+    }
+
+    #[test]
+    fn conv1d() -> Result<(), Box<dyn Error>> {
+
+        let xs = images(2, (1, 9), 2);
+        let conv = Conv1d::new(2, 4, 3, 9).with_stride(2).with_padding(1).with_dilation(2);
+        assert_eq!(conv.output_shape(), (4, 4));
+
+        let ys = conv.forward(&xs)?;
+        assert_eq!(ys.shape(), (4 * 4, 2));
+        assert_eq!(conv.describe(), "Conv1d(2x9 -> 4x4, kernel 3, stride 2, padding 1, dilation 2)");
+
+        // a signal is a feature map of height 1, padded along its length only
+        let params = conv.parameters();
+        let expected = naive_conv(&xs, params[0].value(), params[1].value(), 2, (1, 9), (1, 3), (1, 2), (0, 1), (1, 2));
+        for (a, b) in ys.data().iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+
+        check_gradients(|xs| conv.forward(&xs[0]), &[xs])?;
+        Ok(())
+    }
+
+    #[test]
+    fn pooling() -> Result<(), Box<dyn Error>> {
+
+        // one channel of 2 x 4, two samples
+        let xs = Matrix::from_vec(vec![
+            1., -1.,
+            2., -2.,
+            5., -5.,
+            0., 0.,
+            3., -3.,
+            4., -4.,
+            6., -6.,
+            7., -7.,
+        ], (8, 2), true);
+
+        let max = MaxPool::new2d(1, (2, 4), (2, 2), (2, 2));
+        assert_eq!(max.output_shape(), (1, 1, 2));
+        assert_eq!(max.forward(&xs)?.data().to_vec(), vec![4., -1., 7., 0.]);
+
+        let avg = AvgPool::new2d(1, (2, 4), (2, 2), (2, 2));
+        assert_eq!(avg.forward(&xs)?.data().to_vec(), vec![2.5, -2.5, 4.5, -4.5]);
+
+        let max = MaxPool::new2d(3, (4, 5), (2, 3), (1, 2));
+        let avg = AvgPool::new1d(3, 20, 4, 3);
+        check_gradients(|xs| max.forward(&xs[0]), &[images(3, (4, 5), 2)])?;
+        check_gradients(|xs| avg.forward(&xs[0]), &[images(3, (4, 5), 2)])?;
+        Ok(())
+    }
+
+    #[test]
+    fn too_small_input() {
+
+        let conv = Conv2d::new(1, 1, (3, 3), (2, 2));
+        assert!(conv.forward(&Matrix::ones((4, 1), false)).is_err());
+        assert!(conv.forward(&Matrix::ones((5, 1), false)).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_stride() {
+        Conv1d::new(1, 1, 2, 8).with_stride(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_dilation() {
+        Conv2d::new(1, 1, (2, 2), (4, 4)).with_dilation((1, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_pool_kernel() {
+        AvgPool::new2d(1, (4, 4), (2, 0), (1, 1));
+    }
+
+    #[test]
+    fn convolutional_model() -> Result<(), Box<dyn Error>> {
+
+        let conv = Conv2d::new(1, 2, (3, 3), (6, 6)).with_padding((1, 1));
+        let pool = MaxPool::new2d(2, (6, 6), (2, 2), (2, 2));
+        let (channels, height, width) = pool.output_shape();
+        let model = Sequential::new()
+            .push(conv)
+            .push(Activation::Relu)
+            .push(pool)
+            .push(Flatten::new())
+            .push(Dense::new(channels * height * width, 1, Activation::Sigmoid));
+
+        let ys = model.forward(&images(1, (6, 6), 4))?;
+        assert_eq!(ys.shape(), (1, 4));
+
+        let grads = ys.sum_all()?.backward()?;
+        for param in model.parameters() {
+            assert_eq!(grads.get_param(param).unwrap().shape(), param.value().shape(), "{}", param.name());
+        }
+        Ok(())
+    }
+}
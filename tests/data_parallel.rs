@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    fn dataset() -> (Vec<Vec<f32>>, Vec<f32>) {
+        let x = (0..12)
+            .map(|i| vec![(i % 4) as f32 / 4., (i / 4) as f32 / 3.])
+            .collect::<Vec<Vec<f32>>>();
+        let y = x.iter().map(|x| if x[0] > x[1] { 1. } else { 0. }).collect();
+        (x, y)
+    }
+
+    #[test]
+    fn parallel_training_matches_single_thread() -> Result<(), Box<dyn Error>> {
+
+        let (x, y) = dataset();
+        let mut single = NN::new(vec![2, 4, 1], 0.1);
+        let mut parallel = single.clone();
+        parallel.set_threads(4);
+
+        // full batches, such that both models see the same samples every step
+        let single_history = single.train(&x, &y, x.len(), 20)?;
+        let parallel_history = parallel.train(&x, &y, x.len(), 20)?;
+
+        for (a, b) in single_history.iter().zip(parallel_history.iter()) {
+            assert!((a - b).abs() < 1e-4, "loss {} vs {}", a, b);
+        }
+        for ((name, a), (_, b)) in single.named_parameters().zip(parallel.named_parameters()) {
+            for (a, b) in a.data().iter().zip(b.data().iter()) {
+                assert!((a - b).abs() < 1e-4, "{}: {} vs {}", name, a, b);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn batch_norm_trains_on_a_single_thread() -> Result<(), Box<dyn Error>> {
+
+        let (x, y) = dataset();
+        let model = Sequential::new()
+            .push(Dense::new(2, 4, Activation::None))
+            .push(BatchNorm::new(4))
+            .push(Activation::Tanh)
+            .push(Dense::new(4, 1, Activation::Sigmoid));
+        let mut single = NN::from_model(model, 0.1);
+        let mut parallel = single.clone();
+        parallel.set_threads(4);
+
+        let single_history = single.train(&x, &y, x.len(), 20)?;
+        let parallel_history = parallel.train(&x, &y, x.len(), 20)?;
+
+        for (a, b) in single_history.iter().zip(parallel_history.iter()) {
+            assert!((a - b).abs() < 1e-4, "loss {} vs {}", a, b);
+        }
+        // the running statistics are updated once per step, with the statistics of the whole batch
+        let state = |nn: &NN| nn.model().state_dict();
+        for ((name, a), (_, b)) in state(&single).iter().zip(state(&parallel).iter()) {
+            for (a, b) in a.data().iter().zip(b.data().iter()) {
+                assert!((a - b).abs() < 1e-4, "{}: {} vs {}", name, a, b);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn more_threads_than_samples() -> Result<(), Box<dyn Error>> {
+
+        let (x, y) = dataset();
+        let mut nn = NN::new(vec![2, 3, 1], 0.1);
+        nn.set_threads(8);
+
+        let history = nn.train(&x, &y, 3, 5)?;
+        assert_eq!(history.len(), 5);
+        assert!(history.iter().all(|loss| loss.is_finite()));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_grad_maps() -> Result<(), Box<dyn Error>> {
+
+        let w = Matrix::<f32>::from_vec(vec![1., 2.], (1, 2), true);
+        let x1 = Matrix::from_vec(vec![3., 4.], (2, 1), false);
+        let x2 = Matrix::from_vec(vec![-1., 5.], (2, 1), false);
+
+        let mut grads = w.matmul(&x1)?.backward()?;
+        grads.merge(w.matmul(&x2)?.backward()?)?;
+
+        assert_eq!(grads.get(w.id()).unwrap().data(), &vec![2., 9.]);
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    #[test]
+    fn dropout_training_mode() -> Result<(), Box<dyn Error>> {
+
+        let dropout = Dropout::new(0.25);
+        let xs = Matrix::ones((100, 40), true);
+        let ys = dropout.forward(&xs)?;
+
+        // kept elements are scaled by 1 / (1 - p)
+        assert!(ys.data().iter().all(|&y| y == 0. || (y - 4. / 3.).abs() < 1e-6));
+        let dropped = ys.data().iter().filter(|&&y| y == 0.).count() as f32 / 4000.;
+        assert!((dropped - 0.25).abs() < 0.05, "dropped {}", dropped);
+
+        // the gradient is masked and scaled like the activations
+        let grads = ys.sum_all()?.backward()?;
+        assert_eq!(grads.get(xs.id()).unwrap().data(), ys.data());
+        Ok(())
+    }
+
+    #[test]
+    fn dropout_eval_mode() -> Result<(), Box<dyn Error>> {
+
+        let mut dropout = Dropout::new(0.9);
+        dropout.set_training(false);
+
+        let xs = Matrix::randn(-1., 1., (10, 10), true);
+        let ys = dropout.forward(&xs)?;
+        assert_eq!(ys.id(), xs.id());
+        Ok(())
+    }
+
+    #[test]
+    fn checkpointing_keeps_the_dropout_mask() -> Result<(), Box<dyn Error>> {
+
+        let mut residual = Residual::new(Dropout::new(0.5));
+        assert!(residual.is_stochastic());
+        residual.set_training(false);
+        assert!(!residual.is_stochastic());
+
+        for checkpointing in [false, true] {
+            let mut model = Sequential::new()
+                .push(Dropout::new(0.5))
+                .push(Activation::Tanh)
+                .push(Residual::new(Dropout::new(0.5)));
+            model.set_checkpointing(checkpointing);
+
+            let xs = Matrix::randn(0.5, 1., (8, 10), true);
+            let ys = model.forward(&xs)?;
+            let grads = ys.sum_all()?.backward()?;
+
+            // with t = tanh(2x) where the first mask keeps x and 0 elsewhere, y is t or 3t
+            // depending on the second mask; the gradient follows from y if the backward
+            // pass uses the masks of the forward pass
+            for ((&x, &y), &g) in xs.data().iter().zip(ys.data().iter()).zip(grads.get(xs.id()).unwrap().data().iter()) {
+                let h = 2. * x;
+                let expected = if y == 0. {
+                    0.
+                } else if (y - h.tanh()).abs() < 1e-6 {
+                    2. * (1. - h.tanh().powi(2))
+                } else {
+                    6. * (1. - h.tanh().powi(2))
+                };
+                assert!((g - expected).abs() < 1e-5, "checkpointing {}", checkpointing);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn dropout_invalid_probability() {
+        Dropout::new(1.);
+    }
+
+    #[test]
+    fn nn_with_dropout() -> Result<(), Box<dyn Error>> {
+
+        let x = vec![vec![0., 0.], vec![0., 1.], vec![1., 0.], vec![1., 1.]];
+        let y = vec![0., 1., 1., 0.];
+
+        let model = Sequential::new()
+            .push(Dense::new(2, 8, Activation::Sigmoid))
+            .push(Dropout::new(0.5))
+            .push(Dense::new(8, 1, Activation::Sigmoid));
+        let mut nn = NN::from_model(model, 0.5);
+
+        // evaluation mode outside of train, so predictions are deterministic
+        let xs = Matrix::from(&x).t();
+        assert_eq!(nn.forward(xs.clone())?.data(), nn.forward(xs.clone())?.data());
+
+        let history = nn.train(&x, &y, 4, 20)?;
+        assert!(history.iter().all(|loss| loss.is_finite()));
+        assert_eq!(nn.forward(xs.clone())?.data(), nn.forward(xs)?.data());
+        Ok(())
+    }
+}
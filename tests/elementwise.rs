@@ -0,0 +1,84 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    type UnaryFn = fn(&Matrix) -> Matrix;
+
+    #[test]
+    fn elementwise_values() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![-2., 0., 0.5, 4.], (2, 2), false);
+        let b = Matrix::from_vec(vec![1., -1., 1., 1.], (2, 2), false);
+
+        assert_eq!(a.abs().data(), &vec![2., 0., 0.5, 4.]);
+        assert_eq!(a.neg().data(), &vec![2., 0., -0.5, -4.]);
+        assert_eq!(a.clamp(-1., 1.).data(), &vec![-1., 0., 0.5, 1.]);
+        assert_eq!(a.maximum(&b)?.data(), &vec![1., 0., 1., 4.]);
+        assert_eq!(a.minimum(&b)?.data(), &vec![-2., -1., 0.5, 1.]);
+        assert_eq!(a.add_scalar(1.).data(), &vec![-1., 1., 1.5, 5.]);
+        assert_eq!(a.sub_scalar(1.).data(), &vec![-3., -1., -0.5, 3.]);
+        assert_eq!(a.div_scalar(2.).data(), &vec![-1., 0., 0.25, 2.]);
+        assert_eq!(b.reciprocal().data(), &vec![1., -1., 1., 1.]);
+        assert_eq!(Matrix::<f32>::zeros((1, 2), false).exp().data(), &vec![1., 1.]);
+
+        // broadcasting applies to maximum and minimum as well
+        let c = Matrix::from_vec(vec![0.], (1, 1), false);
+        assert_eq!(a.maximum(&c)?.data(), &vec![0., 0., 0.5, 4.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn elementwise_gradients() -> Result<(), Box<dyn Error>> {
+        let x = Matrix::from_vec(vec![-1.5, -0.7, 0.3, 1.1, 0.8, -0.2], (2, 3), true);
+        let positive = Matrix::from_vec(vec![0.5, 1.2, 2., 3.1, 0.9, 1.7], (2, 3), true);
+
+        let ops: Vec<(UnaryFn, &Matrix)> = vec![
+            (|x| x.exp(), &x),
+            (|x| x.ln(), &positive),
+            (|x| x.log1p(), &positive),
+            (|x| x.sqrt(), &positive),
+            (|x| x.abs(), &x),
+            (|x| x.neg(), &x),
+            (|x| x.sin(), &x),
+            (|x| x.cos(), &x),
+            (|x| x.tanh(), &x),
+            (|x| x.sigmoid(), &x),
+            (|x| x.reciprocal(), &positive),
+            (|x| x.clamp(-1., 0.5), &x),
+            (|x| x.add_scalar(2.), &x),
+            (|x| x.sub_scalar(2.), &x),
+            (|x| x.div_scalar(4.), &x),
+            (|x| x.mul_scalar(-3.), &x),
+            (|x| x.powf(3.), &x),
+        ];
+
+        for (op, input) in ops.into_iter() {
+            check_gradients(|xs| Ok(op(&xs[0])), std::slice::from_ref(input))?;
+        }
+
+        let y = Matrix::from_vec(vec![-1., 0.1, 0.4, 0.7, 1.5, -1.], (2, 3), true);
+        check_gradients(|xs| xs[0].maximum(&xs[1]), &[x.clone(), y.clone()])?;
+        check_gradients(|xs| xs[0].minimum(&xs[1]), &[x.clone(), y.clone()])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn maximum_splits_gradient_on_ties() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let b = Matrix::from_vec(vec![1., 3.], (1, 2), true);
+
+        let grads = a.maximum(&b)?.backward()?;
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![0.5, 0.]);
+        assert_eq!(grads.get(b.id()).unwrap().data(), &vec![0.5, 1.]);
+
+        Ok(())
+    }
+}
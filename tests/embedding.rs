@@ -0,0 +1,173 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    #[test]
+    fn lookup() -> Result<(), Box<dyn Error>> {
+
+        let embedding = Embedding::new(4, 3);
+        let weight = embedding.weight().value();
+
+        // two categorical features, two samples
+        let xs = Matrix::from_vec(vec![2., 0., 3., 3.], (2, 2), false);
+        let ys = embedding.forward(&xs)?;
+        assert_eq!(ys.shape(), (6, 2));
+        for d in 0..3 {
+            assert_eq!(ys.get(d, 0), weight.get(2, d));
+            assert_eq!(ys.get(d, 1), weight.get(0, d));
+            assert_eq!(ys.get(3 + d, 0), weight.get(3, d));
+            assert_eq!(ys.get(3 + d, 1), weight.get(3, d));
+        }
+
+        for index in [1.5, -1., 4.] {
+            let err = embedding.forward(&Matrix::from_vec(vec![index], (1, 1), false)).unwrap_err();
+            assert!(matches!(err, MatrixError::InvalidIndex { len: 4, .. }));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gradients_only_reach_used_rows() -> Result<(), Box<dyn Error>> {
+
+        let embedding = Embedding::new(5, 2);
+        let xs = Matrix::from_vec(vec![1., 3., 1.], (1, 3), false);
+        let grads = embedding.forward(&xs)?.sum_all()?.backward()?;
+
+        // the gradient only holds the rows that were looked up, row 1 is used twice
+        let weight = embedding.weight().value();
+        assert!(grads.get_param(embedding.weight()).is_none());
+        let rows = grads.get_rows(weight.id()).unwrap();
+        assert_eq!(rows.shape(), (5, 2));
+        assert_eq!(rows.rows(), &[1, 3]);
+        assert_eq!(rows.values(), &[2., 2., 1., 1.]);
+        assert_eq!(rows.to_dense().data(), &vec![0., 0., 2., 2., 0., 0., 1., 1., 0., 0.]);
+        assert_eq!(grads.norm(weight), Some(10f32.sqrt()));
+
+        check_gradients(|ws| {
+            let mut embedding = embedding.clone();
+            embedding.parameters_mut()[0].set(ws[0].clone());
+            embedding.forward(&xs)
+        }, &[Matrix::from_vec(weight.data().to_vec(), weight.shape(), true)])?;
+        Ok(())
+    }
+
+    #[test]
+    fn row_gradients_become_dense_with_dense_terms() -> Result<(), Box<dyn Error>> {
+
+        let embedding = Embedding::new(3, 2);
+        let weight = embedding.weight().value();
+        let xs = Matrix::from_vec(vec![2.], (1, 1), false);
+
+        // an L2 penalty on the whole table reaches every row
+        let penalty = weight.powf(2.).sum_all()?;
+        let grads = embedding.forward(&xs)?.sum_all()?.add(&penalty)?.backward()?;
+        assert!(grads.get_rows(weight.id()).is_none());
+        let grad = grads.get(weight.id()).unwrap();
+        for (k, (g, w)) in grad.data().iter().zip(weight.data().iter()).enumerate() {
+            let looked_up = if k / 2 == 2 { 1. } else { 0. };
+            assert!((g - (2. * w + looked_up)).abs() < 1e-6);
+        }
+
+        // a table used by a hook gets a dense gradient, which is what the hook sees
+        let shapes = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let shapes_c = shapes.clone();
+        weight.register_hook(move |grad| {
+            shapes_c.lock().unwrap().push(grad.shape());
+            None
+        });
+        let grads = embedding.forward(&xs)?.sum_all()?.backward()?;
+        assert_eq!(grads.get(weight.id()).unwrap().data(), &vec![0., 0., 0., 0., 1., 1.]);
+        assert_eq!(*shapes.lock().unwrap(), vec![(3, 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_updates_match_in_parallel() -> Result<(), Box<dyn Error>> {
+
+        let (x, y) = mixed_data();
+        let model = Sequential::new()
+            .push(FeatureEmbedding::new(2).with_embedding(1, Embedding::new(3, 2)))
+            .push(Dense::new(3, 1, Activation::Sigmoid));
+        let mut single = NN::from_model(model, 0.5);
+        single.set_grad_clip(Some(GradClip::Norm(1.)));
+        let mut parallel = single.clone();
+        parallel.set_threads(3);
+
+        // full batches, such that the shards hold different rows of the table
+        let single_history = single.train(&x, &y, x.len(), 10)?;
+        let parallel_history = parallel.train(&x, &y, x.len(), 10)?;
+        for (a, b) in single_history.iter().zip(parallel_history.iter()) {
+            assert!((a - b).abs() < 1e-4, "loss {} vs {}", a, b);
+        }
+        for ((name, a), (_, b)) in single.named_parameters().zip(parallel.named_parameters()) {
+            for (a, b) in a.data().iter().zip(b.data().iter()) {
+                assert!((a - b).abs() < 1e-4, "{}: {} vs {}", name, a, b);
+            }
+        }
+        Ok(())
+    }
+
+    // y depends on the category without any ordering, plus a numeric feature
+    fn mixed_data() -> (Vec<Vec<f32>>, Vec<f32>) {
+        let effects = [0.8, 0.1, 0.9];
+        (0..30)
+            .map(|i| {
+                let (category, x) = (i % 3, (i % 5) as f32 / 5.);
+                (vec![x, category as f32], (effects[category] + x) / 2.)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn mixed_features_are_trained() -> Result<(), Box<dyn Error>> {
+
+        let (x, y) = mixed_data();
+
+        // category 3 never occurs
+        let input = FeatureEmbedding::new(2).with_embedding(1, Embedding::new(4, 2));
+        assert_eq!(input.outputs(), 3);
+        let model = Sequential::new()
+            .push(input)
+            .push(Dense::new(3, 4, Activation::Tanh))
+            .push(Dense::new(4, 1, Activation::Sigmoid));
+
+        let mut nn = NN::from_model(model, 0.5);
+        let initial = nn.parameter("0.1.weight").unwrap().value().clone();
+
+        let history = nn.train(&x, &y, 10, 1000)?;
+        assert!(history.last().unwrap() < &5e-3);
+
+        // the rows of unseen categories are never part of a gradient and keep their values,
+        // while the clone of the table taken before training is not updated in place
+        let weight = nn.parameter("0.1.weight").unwrap().value().clone();
+        assert_eq!(weight.slice_rows(3, 4)?.data(), initial.slice_rows(3, 4)?.data());
+        assert_ne!(weight.slice_rows(0, 3)?.data(), initial.slice_rows(0, 3)?.data());
+        Ok(())
+    }
+
+    #[test]
+    fn label_encoding() -> Result<(), Box<dyn Error>> {
+
+        let path = std::env::temp_dir().join(format!("label_encode_{}.csv", std::process::id()));
+        std::fs::write(&path, "size,color\n1.5,red\n2,blue\n0.5,red\n")?;
+
+        let mut df = DataFrame::from_csv(path.to_str().unwrap())?;
+        assert!(df.to_matrix::<f32>().is_err());
+
+        let categories = df.label_encode("color")?;
+        assert_eq!(categories, vec!["red", "blue"]);
+        assert_eq!(df.to_matrix::<f32>()?.data(), &vec![1.5, 0., 2., 1., 0.5, 0.]);
+
+        assert!(matches!(df.label_encode("weight"), Err(UtilityError::UnknownColumn { .. })));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    #[test]
+    fn grad_norms_and_stats() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::from_vec(vec![3., 4.], (1, 2), true);
+        let b = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+
+        // da = [3, 4], db = [-1, -1]
+        let c = a.mul(&a)?.mul_scalar(0.5).sub(&b)?;
+        let grads = c.backward()?;
+
+        assert_eq!(grads.norm(&a), Some(5.));
+        assert_eq!(grads.norms(&[&a, &b]), vec![(a.id(), 5.), (b.id(), 2.0_f32.sqrt())]);
+        assert_eq!(grads.global_norm(&[&a, &b]), 27.0_f32.sqrt());
+
+        let stats = grads.stats(&[&a, &b]);
+        assert_eq!(stats.max, 4.);
+        assert_eq!(stats.min, -1.);
+        assert_eq!(stats.nan_count, 0);
+        assert_eq!(stats.inf_count, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn grad_clipping() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::from_vec(vec![3., -4.], (1, 2), true);
+        let b = Matrix::from_vec(vec![0., 0.], (1, 2), true);
+
+        let c = a.mul(&a)?.mul_scalar(0.5).add(&b)?;
+
+        let mut grads = c.backward()?;
+        grads.clip_value(&[&a], -1., 1.);
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![1., -1.]);
+
+        let mut grads = c.backward()?;
+        let norm = grads.clip_norm(&[&a, &b], 1.);
+        assert_eq!(norm, 27.0_f32.sqrt());
+        assert!((grads.global_norm(&[&a, &b]) - 1.).abs() < 1e-5);
+
+        // norm below the threshold leaves the gradients untouched
+        let mut grads = c.backward()?;
+        grads.clip_norm(&[&a, &b], 10.);
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![3., -4.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn grad_stats_count_non_finite() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::from_vec(vec![0., 1.], (1, 2), true);
+        let b = Matrix::from_vec(vec![1., 1.], (1, 2), false);
+
+        // d(b/a)/da = -b/a^2 which is -inf for a = 0
+        let c = b.div(&a)?;
+        let grads = c.backward()?;
+
+        let stats = grads.stats(&[&a]);
+        assert_eq!(stats.inf_count, 1);
+        assert_eq!(stats.nan_count, 0);
+        assert_eq!(stats.max, -1.);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_clip_value() {
+        let mut nn = NN::new(vec![2, 1], 0.1);
+        nn.set_grad_clip(Some(GradClip::Value(-0.5)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn nan_clip_norm() {
+        let mut nn = NN::new(vec![2, 1], 0.1);
+        nn.set_grad_clip(Some(GradClip::Norm(f32::NAN)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn inverted_clip_range() {
+        let a = Matrix::from_vec(vec![3., -4.], (1, 2), true);
+        let mut grads = a.sum_all().unwrap().backward().unwrap();
+        grads.clip_value(&[&a], 1., -1.);
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    #[test]
+    fn graph_export_deduplicates_shared_nodes() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let b = Matrix::from_vec(vec![3., 4.], (1, 2), false);
+
+        // c is used twice, the graph should still contain it once
+        let c = a.mul(&b)?;
+        let d = c.add(&c)?;
+
+        let nodes = d.graph_nodes();
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes.last().unwrap().id(), d.id());
+
+        let dot = d.to_dot(None);
+        assert!(dot.starts_with("digraph {"));
+        assert_eq!(dot.matches(&format!("n{} [", c.id())).count(), 1);
+        assert_eq!(dot.matches(&format!("n{} -> n{};", c.id(), d.id())).count(), 2);
+        assert!(dot.contains(&format!("n{} -> n{};", a.id(), c.id())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn graph_export_json() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::<f32>::from_vec(vec![3., 4.], (2, 1), true);
+        let b = a.mul_scalar(2.);
+
+        let grads = b.backward()?;
+        let json = b.to_json(Some(&grads));
+
+        assert_eq!(json, format!(
+            "{{\"nodes\":[\
+                {{\"id\":{a},\"op\":null,\"shape\":[2,1],\"requires_grad\":true,\"grad_norm\":{norm}}},\
+                {{\"id\":{b},\"op\":\"MulScalar\",\"shape\":[2,1],\"requires_grad\":true,\"grad_norm\":{one}}}\
+            ],\"edges\":[{{\"from\":{a},\"to\":{b}}}]}}",
+            a=a.id(), b=b.id(), norm=8.0_f32.sqrt(), one=2.0_f32.sqrt()
+        ));
+
+        let json = b.to_json(None);
+        assert!(json.contains("\"grad_norm\":null"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+
+    use std::{error::Error, sync::{Arc, Mutex}};
+
+    use neural_network::*;
+
+    #[test]
+    fn hooks_observe_gradients() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let b = a.mul_scalar(3.);
+        let c = b.mul(&b)?;
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let seen_b = seen.clone();
+        b.register_hook(move |grad| {
+            seen_b.lock().unwrap().push(grad.data().clone());
+            None
+        });
+
+        let grads = c.backward()?;
+
+        // the hook sees the accumulated gradient 2b once
+        assert_eq!(*seen.lock().unwrap(), vec![vec![6., 12.]]);
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![18., 36.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_replace_gradients() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let b = a.mul_scalar(3.);
+        let c = b.sigmoid();
+
+        // masking an intermediate gradient stops it from reaching a
+        let handle = b.register_hook(|grad| Some(Matrix::zeros(grad.shape(), false)));
+        let grads = c.backward()?;
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![0., 0.]);
+
+        // hooks on leaves scale the final gradient
+        assert!(b.remove_hook(handle));
+        assert!(!b.remove_hook(handle));
+        a.register_hook(|grad| Some(grad.mul_scalar(0.5)));
+        a.register_hook(|grad| Some(grad.mul_scalar(4.)));
+
+        let grads = c.backward()?;
+
+        let unhooked = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let unhooked_grads = unhooked.mul_scalar(3.).sigmoid().backward()?;
+        
+        let hooked = grads.get(a.id()).unwrap().data();
+        let unhooked = unhooked_grads.get(unhooked.id()).unwrap().data();
+        for (x, y) in hooked.iter().zip(unhooked.iter()) {
+            assert!((x - 2. * y).abs() < 1e-6);
+        }
+
+        // replacing a gradient with a different shape is an error
+        a.register_hook(|_| Some(Matrix::zeros((2, 2), false)));
+        assert!(c.backward().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn handles_belong_to_their_matrix() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 2.], (1, 2), true);
+        let b = Matrix::from_vec(vec![3., 4.], (1, 2), true);
+
+        let handle_a = a.register_hook(|grad| Some(grad.mul_scalar(2.)));
+        let handle_b = b.register_hook(|grad| Some(grad.mul_scalar(3.)));
+        assert_ne!(handle_a, handle_b);
+
+        // a handle of a does not remove the hook of b
+        assert!(!b.remove_hook(handle_a));
+        let grads = a.add(&b)?.backward()?;
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![2., 2.]);
+        assert_eq!(grads.get(b.id()).unwrap().data(), &vec![3., 3.]);
+
+        assert!(a.remove_hook(handle_a));
+        assert!(b.remove_hook(handle_b));
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    fn sample() -> Matrix {
+        Matrix::from_vec(vec![1., 2., 3., 4., 5., 6., 7., 8., 9., 10., 11., 12.], (3, 4), true)
+    }
+
+    #[test]
+    fn slicing_and_selection() -> Result<(), Box<dyn Error>> {
+        let a = sample();
+
+        let c = a.slice_rows(1, 3)?;
+        assert_eq!(c.shape(), (2, 4));
+        assert_eq!(c.data(), &vec![5., 6., 7., 8., 9., 10., 11., 12.]);
+
+        let c = a.slice_cols(1, 3)?;
+        assert_eq!(c.shape(), (3, 2));
+        assert_eq!(c.data(), &vec![2., 3., 6., 7., 10., 11.]);
+
+        let c = a.select(0, &[2, 0, 2])?;
+        assert_eq!(c.data(), &vec![9., 10., 11., 12., 1., 2., 3., 4., 9., 10., 11., 12.]);
+
+        let c = a.select(1, &[3, 0])?;
+        assert_eq!(c.data(), &vec![4., 1., 8., 5., 12., 9.]);
+
+        let c = a.gather(1, &[0, 3, 1])?;
+        assert_eq!(c.shape(), (3, 1));
+        assert_eq!(c.data(), &vec![1., 8., 10.]);
+
+        let c = a.gather(0, &[2, 0, 1, 1])?;
+        assert_eq!(c.shape(), (1, 4));
+        assert_eq!(c.data(), &vec![9., 2., 7., 8.]);
+
+        let src = Matrix::from_vec(vec![10., 20., 30.], (3, 1), false);
+        let c = Matrix::zeros((3, 4), false).scatter_add(1, &[0, 3, 0], &src)?;
+        assert_eq!(c.data(), &vec![10., 0., 0., 0., 0., 0., 0., 20., 30., 0., 0., 0.]);
+
+        assert!(matches!(a.slice_rows(2, 4), Err(MatrixError::IndexOutOfBounds { .. })));
+        assert!(matches!(a.slice_cols(2, 1), Err(MatrixError::InvalidRange { start: 2, end: 1, .. })));
+        assert!(matches!(a.select(1, &[4]), Err(MatrixError::IndexOutOfBounds { .. })));
+        assert!(a.gather(1, &[0, 1]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn concat_stack_split() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 2., 3., 4.], (2, 2), false);
+        let b = Matrix::from_vec(vec![5., 6.], (2, 1), false);
+        let c = Matrix::from_vec(vec![7., 8.], (1, 2), false);
+
+        let ab = Matrix::concat(&[&a, &b], 1)?;
+        assert_eq!(ab.shape(), (2, 3));
+        assert_eq!(ab.data(), &vec![1., 2., 5., 3., 4., 6.]);
+
+        let ac = Matrix::concat(&[&a, &c], 0)?;
+        assert_eq!(ac.shape(), (3, 2));
+        assert_eq!(ac.data(), &vec![1., 2., 3., 4., 7., 8.]);
+
+        assert!(Matrix::concat(&[&a, &c], 1).is_err());
+
+        let s = Matrix::stack(&[&a, &a.mul_scalar(2.)], 1)?;
+        assert_eq!(s.shape(), (4, 2));
+        assert_eq!(s.data(), &vec![1., 2., 2., 4., 3., 6., 4., 8.]);
+
+        let parts = ab.split(1, &[2, 1])?;
+        assert_eq!(parts[0].data(), a.data());
+        assert_eq!(parts[1].data(), b.data());
+        assert!(ab.split(1, &[2, 2]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn indexing_gradients() -> Result<(), Box<dyn Error>> {
+        let a = sample();
+        let b = Matrix::randn(-1., 1., (3, 2), true);
+        let src = Matrix::randn(-1., 1., (3, 1), true);
+
+        check_gradients(|xs| xs[0].slice_rows(1, 3), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].slice_cols(0, 2), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].select(0, &[2, 0, 2]), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].select(1, &[1, 1, 3]), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].gather(1, &[0, 3, 1]), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].gather(0, &[2, 0, 1, 1]), std::slice::from_ref(&a))?;
+        check_gradients(|xs| xs[0].scatter_add(1, &[0, 3, 0], &xs[1]), &[a.clone(), src])?;
+        check_gradients(|xs| Matrix::concat(&[&xs[0], &xs[1]], 1), &[a.clone(), b.clone()])?;
+        check_gradients(|xs| Matrix::stack(&[&xs[0], &xs[1].powf(2.)], 0), &[b.clone(), Matrix::randn(-1., 1., (3, 2), true)])?;
+        check_gradients(|xs| {
+            let parts = xs[0].split(0, &[1, 2])?;
+            parts[1].mul_scalar(2.).sum(0)?.add(&parts[0])
+        }, std::slice::from_ref(&a))?;
+
+        Ok(())
+    }
+}
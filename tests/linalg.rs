@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    fn assert_close(a: &Matrix, b: &Matrix) {
+        assert_eq!(a.shape(), b.shape());
+        for (x, y) in a.data().iter().zip(b.data().iter()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a.data(), b.data());
+        }
+    }
+
+    fn identity(n: usize) -> Matrix {
+        let data = (0..n * n).map(|k| if k / n == k % n { 1. } else { 0. }).collect();
+        Matrix::from_vec(data, (n, n), false)
+    }
+
+    fn diag(v: &Matrix) -> Matrix {
+        let n = v.shape().0;
+        let data = (0..n * n).map(|k| if k / n == k % n { v.get(k / n, 0) } else { 0. }).collect();
+        Matrix::from_vec(data, (n, n), false)
+    }
+
+    fn square() -> Matrix {
+        Matrix::from_vec(vec![0., 2., 1., 1., 1., 0., 3., 0., 1.], (3, 3), false)
+    }
+
+    #[test]
+    fn lu_det_inverse_solve() -> Result<(), Box<dyn Error>> {
+        let a = square();
+
+        let lu = a.lu()?;
+        let pa = a.select(0, &lu.perm)?;
+        assert_close(&lu.l.matmul(&lu.u)?, &pa);
+
+        assert!((a.det()? + 5.).abs() < 1e-5);
+        assert_close(&a.matmul(&a.inverse()?)?, &identity(3));
+
+        let b = Matrix::from_vec(vec![3., 2., 4.], (3, 1), false);
+        assert_close(&a.solve(&b)?, &Matrix::from_vec(vec![1., 1., 1.], (3, 1), false));
+
+        let singular = Matrix::from_vec(vec![1., 2., 2., 4.], (2, 2), false);
+        assert_eq!(singular.det()?, 0.);
+        assert!(matches!(singular.inverse(), Err(MatrixError::SingularMatrix { .. })));
+        assert!(matches!(singular.solve(&b), Err(MatrixError::ShapeMismatchError { .. })));
+
+        let rect = Matrix::<f32>::zeros((2, 3), false);
+        assert!(matches!(rect.det(), Err(MatrixError::NotSquare { .. })));
+        assert!(matches!(rect.inverse(), Err(MatrixError::NotSquare { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn qr_decomposition() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![12., -51., 4., 6., 167., -68., -4., 24., -41.], (3, 3), false);
+        let Qr { q, r } = a.qr()?;
+
+        assert_close(&q.matmul(&r)?, &a);
+        assert_close(&q.t().matmul(&q)?, &identity(3));
+        assert!((r.get(0, 0).abs() - 14.).abs() < 1e-4);
+        assert!((r.get(1, 1).abs() - 175.).abs() < 1e-3);
+        assert_eq!(r.get(2, 0), 0.);
+
+        let tall = Matrix::from_vec(vec![1., 2., 3., 4., 5., 6.], (3, 2), false);
+        let Qr { q, r } = tall.qr()?;
+        assert_eq!((q.shape(), r.shape()), ((3, 3), (3, 2)));
+        assert_close(&q.matmul(&r)?, &tall);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cholesky_decomposition() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![4., 12., -16., 12., 37., -43., -16., -43., 98.], (3, 3), false);
+        let l = a.cholesky()?;
+        assert_close(&l, &Matrix::from_vec(vec![2., 0., 0., 6., 1., 0., -8., 5., 3.], (3, 3), false));
+
+        let indefinite = Matrix::from_vec(vec![1., 2., 2., 1.], (2, 2), false);
+        assert!(matches!(indefinite.cholesky(), Err(MatrixError::NotPositiveDefinite { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_eigen_decomposition() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![2., 1., 0., 1., 2., 0., 0., 0., 5.], (3, 3), false);
+        let Eigh { values, vectors } = a.eigh()?;
+
+        assert_close(&values, &Matrix::from_vec(vec![1., 3., 5.], (3, 1), false));
+        assert_close(&vectors.matmul(&diag(&values))?.matmul(&vectors.t())?, &a);
+        assert_close(&vectors.t().matmul(&vectors)?, &identity(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn singular_value_decomposition() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![3., 2., 2., 2., 3., -2.], (2, 3), false);
+        let Svd { u, s, vt } = a.svd()?;
+
+        assert_eq!((u.shape(), s.shape(), vt.shape()), ((2, 2), (2, 1), (2, 3)));
+        assert_close(&s, &Matrix::from_vec(vec![5., 3.], (2, 1), false));
+        assert_close(&u.matmul(&diag(&s))?.matmul(&vt)?, &a);
+
+        let tall = a.t();
+        let Svd { u, s, vt } = tall.svd()?;
+        assert_close(&s, &Matrix::from_vec(vec![5., 3.], (2, 1), false));
+        assert_close(&u.matmul(&diag(&s))?.matmul(&vt)?, &tall);
+
+        Ok(())
+    }
+
+    #[test]
+    fn least_squares() -> Result<(), Box<dyn Error>> {
+        // fit y = 1 + 2x through noiseless points
+        let a = Matrix::from_vec(vec![1., 0., 1., 1., 1., 2., 1., 3.], (4, 2), false);
+        let b = Matrix::from_vec(vec![1., 3., 5., 7.], (4, 1), false);
+        assert_close(&a.lstsq(&b)?, &Matrix::from_vec(vec![1., 2.], (2, 1), false));
+
+        // the normal equations hold for an inconsistent system
+        let b = Matrix::from_vec(vec![1., 2., 2., 5.], (4, 1), false);
+        let x = a.lstsq(&b)?;
+        let residual = a.matmul(&x)?.sub(&b)?;
+        assert_close(&a.t().matmul(&residual)?, &Matrix::zeros((2, 1), false));
+
+        let rank_deficient = Matrix::from_vec(vec![1., 2., 2., 4., 3., 6.], (3, 2), false);
+        assert!(matches!(rank_deficient.lstsq(&Matrix::zeros((3, 1), false)), Err(MatrixError::SingularMatrix { .. })));
+
+        Ok(())
+    }
+}
@@ -83,5 +83,6 @@ mod tests {
         let c = a.t();
         
         assert_eq!(c.shape(), (3, 2));
+        assert_eq!(c.data(), &vec![1., 1., 2., 4., -2., 6.]);
     }
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    // y = scale * x, with a learnable scale; doubles the output in training mode
+    #[derive(Debug, Clone)]
+    struct Scale {
+        scale: Parameter,
+        training: bool
+    }
+
+    impl Scale {
+        fn new(scale: f32) -> Self {
+            Self { scale: Parameter::new("scale", Matrix::from_vec(vec![scale], (1, 1), true)), training: false }
+        }
+    }
+
+    impl Module for Scale {
+        fn forward(&self, xs: &Matrix) -> Result<Matrix, MatrixError> {
+            let ys = xs.mul(self.scale.value())?;
+            Ok(if self.training { ys.mul_scalar(2.) } else { ys })
+        }
+
+        fn describe(&self) -> String {
+            "Scale".to_string()
+        }
+
+        fn parameters(&self) -> Vec<&Parameter> {
+            vec![&self.scale]
+        }
+
+        fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+            vec![&mut self.scale]
+        }
+
+        fn set_training(&mut self, training: bool) {
+            self.training = training;
+        }
+    }
+
+    #[test]
+    fn sequential_parameter_names() {
+
+        let model = Sequential::new()
+            .push(Dense::new(2, 3, Activation::Relu))
+            .push(Scale::new(1.))
+            .push(Dense::new(3, 1, Activation::None));
+
+        let names = model.parameters().iter().map(|param| param.name().to_string()).collect::<Vec<String>>();
+        assert_eq!(names, vec!["0.weight", "0.bias", "1.scale", "2.weight", "2.bias"]);
+        assert_eq!(model.num_parameters(), 6 + 3 + 1 + 3 + 1);
+        assert_eq!(model.forward(&Matrix::ones((2, 5), false)).unwrap().shape(), (1, 5));
+    }
+
+    #[test]
+    fn custom_module_is_trained() -> Result<(), Box<dyn Error>> {
+
+        // learn y = 3x
+        let x = vec![vec![1.], vec![2.], vec![-1.], vec![0.5]];
+        let y = x.iter().map(|x| 3. * x[0]).collect::<Vec<f32>>();
+
+        let mut nn = NN::from_model(Sequential::new().push(Scale::new(0.)), 0.01);
+        let history = nn.train(&x, &y, 4, 50)?;
+
+        assert!(history.last().unwrap() < &1e-3);
+        // train runs in training mode, where Scale doubles its output
+        let scale = nn.parameter("0.scale").unwrap().value().get(0, 0);
+        assert!((scale - 1.5).abs() < 1e-2);
+        Ok(())
+    }
+
+    #[test]
+    fn training_mode_is_propagated() -> Result<(), Box<dyn Error>> {
+
+        let mut model = Sequential::new().push(Sequential::new().push(Scale::new(2.)));
+        let xs = Matrix::from_vec(vec![1.], (1, 1), false);
+
+        assert_eq!(model.forward(&xs)?.get(0, 0), 2.);
+        model.set_training(true);
+        assert_eq!(model.forward(&xs)?.get(0, 0), 4.);
+        model.set_training(false);
+        assert_eq!(model.forward(&xs)?.get(0, 0), 2.);
+        Ok(())
+    }
+
+    #[test]
+    fn summary() {
+
+        let nn = NN::new(vec![2, 4, 1], 0.1);
+        let summary = nn.summary();
+
+        assert!(summary.contains("Sequential"));
+        assert!(summary.contains("  Dense(2 -> 4, Sigmoid)"));
+        assert!(summary.contains("  Dense(4 -> 1, Sigmoid)"));
+        assert!(summary.contains("Total parameters: 17"));
+    }
+
+    #[test]
+    fn save_and_load() -> Result<(), Box<dyn Error>> {
+
+        let path = std::env::temp_dir().join(format!("nn_save_and_load_{}.txt", std::process::id()));
+        let nn = NN::new(vec![2, 3, 1], 0.1);
+        nn.save(&path)?;
+
+        let mut other = NN::new(vec![2, 3, 1], 0.1);
+        other.load(&path)?;
+        for ((name, a), (_, b)) in nn.named_parameters().zip(other.named_parameters()) {
+            assert_eq!(a.data(), b.data(), "{}", name);
+            assert_eq!(a.shape(), b.shape(), "{}", name);
+        }
+
+        let mut wrong_shape = NN::new(vec![2, 4, 1], 0.1);
+        let err = wrong_shape.load(&path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NNError>(),
+            Some(NNError::ParameterShapeMismatch { expected: (4, 2), got: (3, 2), .. })
+        ));
+
+        let mut deeper = NN::new(vec![2, 3, 1, 1], 0.1);
+        let err = deeper.load(&path).unwrap_err();
+        assert!(matches!(err.downcast_ref::<NNError>(), Some(NNError::MissingParameter { .. })));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    fn batch() -> Matrix {
+        // 3 features, 4 samples
+        Matrix::from_vec(vec![
+            1., 2., 3., 6.,
+            -1., 0.5, 0., 2.,
+            10., 12., 9., 13.,
+        ], (3, 4), true)
+    }
+
+    #[test]
+    fn batch_norm_training() -> Result<(), Box<dyn Error>> {
+
+        let bn = BatchNorm::new(3);
+        let ys = bn.forward(&batch())?;
+
+        let mean = ys.mean(1)?;
+        let var = ys.var(1)?;
+        for i in 0..3 {
+            assert!(mean.get(i, 0).abs() < 1e-5);
+            assert!((var.get(i, 0) - 1.).abs() < 1e-3);
+        }
+
+        // running = 0.9 * running + 0.1 * batch, with the unbiased batch variance
+        let xs = batch();
+        let (batch_mean, batch_var) = (xs.mean(1)?, xs.var(1)?.mul_scalar(4. / 3.));
+        for i in 0..3 {
+            assert!((bn.running_mean().get(i, 0) - 0.1 * batch_mean.get(i, 0)).abs() < 1e-5);
+            assert!((bn.running_var().get(i, 0) - (0.9 + 0.1 * batch_var.get(i, 0))).abs() < 1e-4);
+        }
+
+        check_gradients(|xs| bn.forward(&xs[0]), &[batch()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn batch_norm_eval() -> Result<(), Box<dyn Error>> {
+
+        let mut bn = BatchNorm::new(3);
+        bn.set_momentum(1.);
+        bn.forward(&batch())?;
+        bn.set_training(false);
+
+        // with momentum 1 the running statistics are those of the last batch
+        let xs = Matrix::from_vec(vec![2., 0., 11.], (3, 1), false);
+        let ys = bn.forward(&xs)?;
+        for i in 0..3 {
+            let expected = (xs.get(i, 0) - bn.running_mean().get(i, 0)) / (bn.running_var().get(i, 0) + 1e-5).sqrt();
+            assert!((ys.get(i, 0) - expected).abs() < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn layer_norm() -> Result<(), Box<dyn Error>> {
+
+        let ln = LayerNorm::new(3);
+        let ys = ln.forward(&batch())?;
+
+        let mean = ys.mean(0)?;
+        for j in 0..4 {
+            assert!(mean.get(0, j).abs() < 1e-5);
+        }
+
+        check_gradients(|xs| ln.forward(&xs[0]), &[batch()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn normalization_parameter_gradients() -> Result<(), Box<dyn Error>> {
+
+        let model = Sequential::new().push(BatchNorm::new(3)).push(LayerNorm::new(3));
+        let grads = model.forward(&batch())?.powf(2.).sum_all()?.backward()?;
+
+        for param in model.parameters() {
+            let grad = grads.get_param(param).unwrap();
+            assert_eq!(grad.shape(), (3, 1), "{}", param.name());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn buffers_are_saved() -> Result<(), Box<dyn Error>> {
+
+        let path = std::env::temp_dir().join(format!("batch_norm_buffers_{}.txt", std::process::id()));
+        let mut model = Sequential::new().push(Dense::new(3, 3, Activation::None)).push(BatchNorm::new(3));
+        model.forward(&batch())?;
+        model.save(&path)?;
+
+        let names = model.state_dict().into_iter().map(|(name, _)| name).collect::<Vec<String>>();
+        assert_eq!(names, vec!["0.weight", "0.bias", "1.weight", "1.bias", "1.running_mean", "1.running_var"]);
+
+        let mut other = Sequential::new().push(Dense::new(3, 3, Activation::None)).push(BatchNorm::new(3));
+        other.load(&path)?;
+        for ((name, a), (_, b)) in model.state_dict().iter().zip(other.state_dict().iter()) {
+            assert_eq!(a.data(), b.data(), "{}", name);
+        }
+
+        model.set_training(false);
+        other.set_training(false);
+        assert_eq!(model.forward(&batch())?.data(), other.forward(&batch())?.data());
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
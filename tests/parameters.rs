@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    #[test]
+    fn named_parameters() {
+        let nn = NN::new(vec![2, 3, 1], 0.1);
+
+        let names = nn.named_parameters().map(|(name, _)| name).collect::<Vec<&str>>();
+        assert_eq!(names, vec!["layers.0.weight", "layers.0.bias", "layers.1.weight", "layers.1.bias"]);
+
+        let shapes = nn.parameters().map(|param| param.value().shape()).collect::<Vec<(usize, usize)>>();
+        assert_eq!(shapes, vec![(3, 2), (3, 1), (1, 3), (1, 1)]);
+
+        assert!(nn.parameter("layers.1.bias").is_some());
+        assert!(nn.parameter("layers.2.bias").is_none());
+    }
+
+    #[test]
+    fn parameters_survive_updates() -> Result<(), Box<dyn Error>> {
+        let x_train = vec![vec![0., 1.], vec![1., 0.]];
+        let y_train = vec![1., 0.];
+
+        let mut nn = NN::new(vec![2, 3, 1], 0.1);
+        let before = nn.parameter("layers.0.weight").unwrap().value().clone();
+
+        nn.train(&x_train, &y_train, 2, 1)?;
+
+        let after = nn.parameter("layers.0.weight").unwrap().value();
+        assert_ne!(before.id(), after.id());
+        assert_ne!(before.data(), after.data());
+
+        // gradients are found through the parameter handle
+        let param = nn.parameter("layers.1.weight").unwrap();
+        let xs: Matrix = x_train.into();
+        let grads = nn.forward(xs.t())?.backward()?;
+        assert_eq!(grads.get_param(param).unwrap().shape(), (1, 3));
+
+        Ok(())
+    }
+}
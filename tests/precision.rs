@@ -0,0 +1,81 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    #[test]
+    fn f64_backward() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::<f64>::from_vec(vec![1., 2., 3., 4.], (2, 2), true);
+        let b = Matrix::<f64>::from_vec(vec![0.5, -1., 2., 0.25], (2, 2), true);
+
+        check_gradients(|xs| xs[0].matmul(&xs[1])?.tanh().mul(&xs[0].ln()), &[a.clone(), b.clone()])?;
+
+        // x = 1 + 2^-30 needs 31 mantissa bits, f32 has 24 and rounds it to 1
+        let x0 = 1. + 2f64.powi(-30);
+        let expected = 3. * x0 * x0;
+        let x = Matrix::<f64>::from_vec(vec![x0], (1, 1), true);
+        let grads = x.powf(3.).backward()?;
+        assert!((grads.get(x.id()).unwrap().get(0, 0) - expected).abs() < 1e-15);
+
+        // the same gradient in f32 is off by 6 * 2^-30
+        let x = x.cast::<f32>();
+        let grads = x.powf(3.).backward()?;
+        assert!((grads.get(x.id()).unwrap().get(0, 0) as f64 - expected).abs() > 5e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cast_between_precisions() {
+
+        let a = Matrix::from_vec(vec![0.1_f32, -2.5, 1e-3], (3, 1), true);
+        let b = a.cast::<f64>();
+
+        assert_eq!(b.shape(), (3, 1));
+        assert!(b.requires_grad());
+        assert!(b.op().is_none());
+        assert_eq!(b.data(), &vec![0.1_f32 as f64, -2.5, 1e-3_f32 as f64]);
+        assert_eq!(b.cast::<f32>().data(), a.data());
+    }
+
+    #[test]
+    fn f64_linalg() -> Result<(), Box<dyn Error>> {
+
+        // Hilbert matrix, badly conditioned
+        let n = 6;
+        let data = (0..n * n)
+            .map(|k| 1. / ((k / n + k % n + 1) as f64))
+            .collect::<Vec<f64>>();
+        let h = Matrix::from_vec(data, (n, n), false);
+
+        let identity = h.matmul(&h.inverse()?)?;
+        for i in 0..n {
+            for j in 0..n {
+                let expected = if i == j { 1. } else { 0. };
+                assert!((identity.get(i, j) - expected).abs() < 1e-6);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dataframe_to_matrix() {
+
+        let df = DataFrame::from_csv("examples/iris/test_input.csv").unwrap();
+
+        match df.to_matrix::<f64>() {
+            Err(UtilityError::NonNumericValue { row, column }) => {
+                assert_eq!(row, 0);
+                assert_eq!(column, "test");
+            },
+            other => panic!("expected NonNumericValue, got {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    fn samples(n: usize) -> Vec<Vec<f32>> {
+        (0..n).map(|i| vec![i as f32 / n as f32, 1. - i as f32 / n as f32, (i % 3) as f32]).collect()
+    }
+
+    #[test]
+    fn predict_matches_forward_in_any_batch_size() -> Result<(), Box<dyn Error>> {
+
+        let mut nn = NN::new(vec![3, 4, 2], 0.1);
+        let x = samples(10);
+        let xs: Matrix = x.clone().into();
+        let expected = nn.forward(xs.t())?.t();
+
+        for batch_size in [1, 3, 10, 256] {
+            nn.set_predict_batch_size(batch_size);
+            let ys = nn.predict(&x)?;
+            assert_eq!(ys.shape(), (10, 2));
+            for (a, b) in ys.data().iter().zip(expected.data().iter()) {
+                assert!((a - b).abs() < 1e-6, "batch size {}", batch_size);
+            }
+            assert_eq!(nn.predict(&xs)?.data(), ys.data());
+            assert_eq!(nn.predict(x.as_slice())?.data(), ys.data());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn predict_from_dataframe() -> Result<(), Box<dyn Error>> {
+
+        let path = std::env::temp_dir().join(format!("predict_{}.csv", std::process::id()));
+        std::fs::write(&path, "a,b\n0,1\n1,0\n0.5,0.5\n")?;
+        let df = DataFrame::from_csv(path.to_str().unwrap())?;
+        std::fs::remove_file(&path)?;
+
+        let nn = NN::new(vec![2, 3, 1], 0.1);
+        let x = vec![vec![0., 1.], vec![1., 0.], vec![0.5, 0.5]];
+        assert_eq!(nn.predict(&df)?.data(), nn.predict(&x)?.data());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_inputs() {
+
+        let nn = NN::new(vec![3, 4, 1], 0.1);
+
+        let err = nn.predict(&vec![vec![0., 1.], vec![1., 0.]]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NNError>(),
+            Some(NNError::FeatureCountMismatch { expected: 3, got: 2 })
+        ));
+
+        let err = nn.predict(&vec![vec![0., 1., 2.], vec![1., 0.]]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NNError>(),
+            Some(NNError::RaggedRows { row: 1, expected: 3, got: 2 })
+        ));
+
+        // the feature count is found behind wrapping modules
+        let model = Sequential::new().push(Residual::new(Dense::new(3, 3, Activation::Tanh)));
+        assert_eq!(model.input_features(), Some(3));
+        let nn = NN::from_model(model, 0.1);
+        assert!(nn.predict(&vec![vec![0.; 4]]).is_err());
+    }
+
+    #[test]
+    fn probabilities_and_classes() -> Result<(), Box<dyn Error>> {
+
+        let x = samples(7);
+
+        // a single output is the probability of class 1
+        let nn = NN::new(vec![3, 4, 1], 0.1);
+        let ys = nn.predict(&x)?;
+        let proba = nn.predict_proba(&x)?;
+        assert_eq!(proba.shape(), (7, 2));
+        for i in 0..7 {
+            assert!((proba.get(i, 1) - ys.get(i, 0)).abs() < 1e-6);
+            assert!((proba.get(i, 0) + proba.get(i, 1) - 1.).abs() < 1e-6);
+        }
+        let expected = ys.data().iter().map(|&p| if p >= 0.5 { 1 } else { 0 }).collect::<Vec<usize>>();
+        assert_eq!(nn.predict_classes(&x)?, expected);
+
+        // several sigmoid outputs are normalized by their sum
+        let nn = NN::new(vec![3, 4, 3], 0.1);
+        let ys = nn.predict(&x)?;
+        let proba = nn.predict_proba(&x)?;
+        assert_eq!(proba.shape(), (7, 3));
+        for i in 0..7 {
+            let sum = (0..3).map(|j| proba.get(i, j)).sum::<f32>();
+            assert!((sum - 1.).abs() < 1e-5);
+        }
+        assert_eq!(nn.predict_classes(&x)?, ys.argmax(1)?);
+        assert_eq!(proba.argmax(1)?, ys.argmax(1)?);
+        Ok(())
+    }
+
+    // a single dense layer whose outputs are its bias, whatever the input
+    fn constant_model(bias: Vec<f32>, activation: Activation) -> Result<NN, Box<dyn Error>> {
+        let outputs = bias.len();
+        let mut model = Sequential::new().push(Dense::new(2, outputs, activation));
+        model.load_state_dict(&[
+            ("0.weight".to_string(), Matrix::zeros((outputs, 2), false)),
+            ("0.bias".to_string(), Matrix::from_vec(bias, (outputs, 1), false)),
+        ])?;
+        Ok(NN::from_model(model, 0.1))
+    }
+
+    #[test]
+    fn probabilities_of_non_negative_outputs() -> Result<(), Box<dyn Error>> {
+
+        let x = vec![vec![0.3, -1.], vec![2., 0.5]];
+
+        // the outputs are normalized as they are, not passed through a softmax
+        let nn = constant_model(vec![1., 2., 1.], Activation::Relu)?;
+        let proba = nn.predict_proba(&x)?;
+        assert_eq!(proba.shape(), (2, 3));
+        for (a, b) in proba.data().iter().zip([0.25, 0.5, 0.25, 0.25, 0.5, 0.25].iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+
+        // a row of zeros is spread evenly
+        let nn = constant_model(vec![0., 0.], Activation::Relu)?;
+        assert_eq!(nn.predict_proba(&x)?.data(), &vec![0.5; 4]);
+
+        // raw scores are rejected
+        let nn = constant_model(vec![1., -2., 0.], Activation::None)?;
+        let err = nn.predict_proba(&x).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NNError>(),
+            Some(NNError::NegativeOutput { sample: 0, value }) if *value == -2.
+        ));
+        let proba = nn.predict(&x)?.softmax(1)?;
+        assert!((proba.get(0, 0) - 1f32.exp() / (1f32.exp() + (-2f32).exp() + 1.)).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn single_output_out_of_range() -> Result<(), Box<dyn Error>> {
+
+        let x = vec![vec![0.3, -1.], vec![2., 0.5]];
+        for bias in [-0.5, 1.5] {
+            let nn = constant_model(vec![bias], Activation::None)?;
+            let err = nn.predict_proba(&x).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<NNError>(),
+                Some(NNError::ProbabilityOutOfRange { sample: 0, value }) if *value == bias
+            ));
+        }
+
+        // the bounds themselves are probabilities
+        let nn = constant_model(vec![1.], Activation::Relu)?;
+        assert_eq!(nn.predict_proba(&x)?.data(), &vec![0., 1., 0., 1.]);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input_keeps_the_outputs() -> Result<(), Box<dyn Error>> {
+
+        let nn = NN::new(vec![3, 4, 2], 0.1);
+        let empty: Vec<Vec<f32>> = vec![];
+        assert_eq!(nn.predict(&empty)?.shape(), (0, 2));
+        assert_eq!(nn.predict(&Matrix::zeros((0, 3), false))?.shape(), (0, 2));
+        assert_eq!(nn.predict_proba(&empty)?.shape(), (0, 2));
+        assert!(nn.predict_classes(&empty)?.is_empty());
+
+        let nn = NN::new(vec![3, 4, 1], 0.1);
+        assert_eq!(nn.predict(&empty)?.shape(), (0, 1));
+        assert_eq!(nn.predict_proba(&empty)?.shape(), (0, 2));
+        Ok(())
+    }
+}
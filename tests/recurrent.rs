@@ -0,0 +1,118 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    // 3 steps of 2 inputs stacked along the rows, 2 samples
+    fn sequence() -> Matrix {
+        Matrix::from_vec(vec![
+            0.5, -0.3,
+            0.1, 0.8,
+            -0.7, 0.2,
+            0.4, 0.4,
+            0.9, -0.6,
+            -0.2, 0.3,
+        ], (6, 2), true)
+    }
+
+    #[test]
+    fn rnn_matches_recurrence() -> Result<(), Box<dyn Error>> {
+
+        let rnn = Rnn::new(2, 3);
+        let params = rnn.parameters();
+        let (w_ih, w_hh, b) = (params[0].value(), params[1].value(), params[2].value());
+
+        let xs = sequence();
+        let mut h = Matrix::zeros((3, 2), false);
+        for t in 0..3 {
+            let x = xs.slice_rows(2 * t, 2 * t + 2)?;
+            h = w_ih.matmul(&x)?.add(&w_hh.matmul(&h)?)?.add(b)?.tanh();
+        }
+
+        let ys = rnn.forward(&xs)?;
+        assert_eq!(ys.shape(), (3, 2));
+        for (a, b) in ys.data().iter().zip(h.data().iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn all_states() -> Result<(), Box<dyn Error>> {
+
+        let lstm = Lstm::new(2, 4);
+        let steps = sequence().split(0, &[2, 2, 2])?;
+        let hidden = lstm.forward_sequence(&steps)?;
+        assert_eq!(hidden.len(), 3);
+
+        let last = lstm.forward(&sequence())?;
+        assert_eq!(last.data(), hidden[2].data());
+
+        let all = lstm.clone().with_all_states().forward(&sequence())?;
+        assert_eq!(all.shape(), (12, 2));
+        assert_eq!(all.slice_rows(4, 8)?.data(), hidden[1].data());
+
+        assert!(lstm.forward(&Matrix::ones((5, 2), false)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn gradients() -> Result<(), Box<dyn Error>> {
+
+        let rnn = Rnn::new(2, 3).with_all_states();
+        let lstm = Lstm::new(2, 3).with_all_states();
+        let gru = Gru::new(2, 3).with_all_states();
+        check_gradients(|xs| rnn.forward(&xs[0]), &[sequence()])?;
+        check_gradients(|xs| lstm.forward(&xs[0]), &[sequence()])?;
+        check_gradients(|xs| gru.forward(&xs[0]), &[sequence()])?;
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_backpropagation() -> Result<(), Box<dyn Error>> {
+
+        let gru = Gru::new(2, 3).with_truncation(2);
+        let steps = (0..4).map(|_| Matrix::ones((2, 1), true)).collect::<Vec<Matrix>>();
+        let hidden = gru.forward_sequence(&steps)?;
+        let grads = hidden[3].sum_all()?.backward()?;
+
+        // the state is detached before step 2, so only the last two steps receive gradients
+        assert!(grads.get(steps[0].id()).is_none());
+        assert!(grads.get(steps[1].id()).is_none());
+        assert!(grads.get(steps[2].id()).is_some());
+        assert!(grads.get(steps[3].id()).is_some());
+
+        // the forward pass is unchanged
+        let full = Gru::new(2, 3);
+        let mut truncated = full.clone().with_truncation(1);
+        truncated.load_state_dict(&full.state_dict())?;
+        assert_eq!(full.forward_sequence(&steps)?[3].data(), truncated.forward_sequence(&steps)?[3].data());
+        Ok(())
+    }
+
+    #[test]
+    fn lstm_remembers_first_step() -> Result<(), Box<dyn Error>> {
+
+        // the label is whether the first of 4 steps is positive, the other steps are noise
+        let x = (0..32)
+            .map(|i| {
+                let first = if i % 2 == 0 { 1. } else { -1. };
+                vec![first, ((i * 7) % 5) as f32 / 5. - 0.4, ((i * 3) % 7) as f32 / 7. - 0.4, ((i * 5) % 3) as f32 / 3. - 0.3]
+            })
+            .collect::<Vec<Vec<f32>>>();
+        let y = x.iter().map(|x| if x[0] > 0. { 1. } else { 0. }).collect::<Vec<f32>>();
+
+        let model = Sequential::new().push(Lstm::new(1, 6)).push(Dense::new(6, 1, Activation::Sigmoid));
+        let mut nn = NN::from_model(model, 0.5);
+        let history = nn.train(&x, &y, 8, 200)?;
+
+        assert!(history.last().unwrap() < &0.05);
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    #[test]
+    fn reduction_values() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![1., 5., 3., 4., 2., 6.], (2, 3), false);
+
+        assert_eq!(a.mean(0)?.data(), &vec![2.5, 3.5, 4.5]);
+        assert_eq!(a.mean(1)?.data(), &vec![3., 4.]);
+        assert_eq!(a.mean_all()?.data(), &vec![3.5]);
+        assert_eq!(a.sum_all()?.shape(), (1, 1));
+
+        assert_eq!(a.max(0)?.data(), &vec![4., 5., 6.]);
+        assert_eq!(a.max(1)?.data(), &vec![5., 6.]);
+        assert_eq!(a.min(0)?.data(), &vec![1., 2., 3.]);
+        assert_eq!(a.min_all()?.data(), &vec![1.]);
+        assert_eq!(a.max_all()?.data(), &vec![6.]);
+
+        assert_eq!(a.argmax(0)?, vec![1, 0, 1]);
+        assert_eq!(a.argmax(1)?, vec![1, 2]);
+        assert_eq!(a.argmin(1)?, vec![0, 1]);
+        assert_eq!(a.argmax_all()?, 5);
+        assert_eq!(a.argmin_all()?, 0);
+
+        assert_eq!(a.var(0)?.data(), &vec![2.25, 2.25, 2.25]);
+        assert_eq!(a.std(0)?.data(), &vec![1.5, 1.5, 1.5]);
+        assert!((a.var_all()?.get(0, 0) - 35. / 12.).abs() < 1e-6);
+
+        let large = Matrix::from_vec(vec![1000., 1000.], (1, 2), false);
+        assert!((large.logsumexp(1)?.get(0, 0) - (1000. + 2.0_f32.ln())).abs() < 1e-3);
+        assert!(large.softmax(1)?.data().iter().all(|p| (p - 0.5).abs() < 1e-3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reductions_reject_invalid_axes() {
+        let a = Matrix::randn(0., 1., (2, 3), false);
+
+        assert!(matches!(a.sum(2), Err(MatrixError::InvalidAxis { axis: 2, .. })));
+        assert!(matches!(a.mean(2), Err(MatrixError::InvalidAxis { axis: 2, .. })));
+        assert!(matches!(a.max(3), Err(MatrixError::InvalidAxis { axis: 3, .. })));
+        assert!(matches!(a.argmin(2), Err(MatrixError::InvalidAxis { axis: 2, .. })));
+        assert!(matches!(a.logsumexp(2), Err(MatrixError::InvalidAxis { axis: 2, .. })));
+    }
+
+    #[test]
+    fn reduction_gradients() -> Result<(), Box<dyn Error>> {
+        let x = Matrix::from_vec(vec![0.3, -1.2, 0.8, 1.5, 0.1, -0.4, 2.2, -0.9, 0.6], (3, 3), true);
+
+        for axis in 0..2 {
+            check_gradients(|xs| xs[0].mean(axis), std::slice::from_ref(&x))?;
+            check_gradients(|xs| xs[0].max(axis), std::slice::from_ref(&x))?;
+            check_gradients(|xs| xs[0].min(axis), std::slice::from_ref(&x))?;
+            check_gradients(|xs| xs[0].var(axis), std::slice::from_ref(&x))?;
+            check_gradients(|xs| xs[0].std(axis), std::slice::from_ref(&x))?;
+            check_gradients(|xs| xs[0].logsumexp(axis), std::slice::from_ref(&x))?;
+            check_gradients(|xs| xs[0].softmax(axis), std::slice::from_ref(&x))?;
+        }
+
+        check_gradients(|xs| xs[0].sum_all(), std::slice::from_ref(&x))?;
+        check_gradients(|xs| xs[0].mean_all(), std::slice::from_ref(&x))?;
+        check_gradients(|xs| xs[0].max_all(), std::slice::from_ref(&x))?;
+        check_gradients(|xs| xs[0].var_all(), std::slice::from_ref(&x))?;
+        check_gradients(|xs| xs[0].logsumexp_all(), std::slice::from_ref(&x))?;
+        check_gradients(|xs| xs[0].reshape((1, 9)), std::slice::from_ref(&x))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_splits_gradient_on_ties() -> Result<(), Box<dyn Error>> {
+        let a = Matrix::from_vec(vec![2., 1., 2.], (1, 3), true);
+        let grads = a.max(1)?.backward()?;
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![0.5, 0., 0.5]);
+
+        Ok(())
+    }
+}
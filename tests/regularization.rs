@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    fn weight() -> Matrix {
+        Matrix::from_vec(vec![0.5, -2., 0., 1.5], (2, 2), true)
+    }
+
+    #[test]
+    fn penalties_and_their_gradients() -> Result<(), Box<dyn Error>> {
+
+        let w = weight();
+        let sign = |x: f32| if x == 0. { 0. } else { x.signum() };
+
+        // l1 * sum(|w|), with gradient l1 * sign(w)
+        let l1 = Penalty::L1(0.1).apply(&w)?;
+        assert!((l1.get(0, 0) - 0.1 * 4.).abs() < 1e-6);
+        let grads = l1.backward()?;
+        let expected = w.data().iter().map(|&x| 0.1 * sign(x)).collect::<Vec<f32>>();
+        assert_eq!(grads.get(w.id()).unwrap().data(), &expected);
+
+        // l2 * sum(w^2), with gradient 2 * l2 * w
+        let l2 = Penalty::L2(0.01).apply(&w)?;
+        assert!((l2.get(0, 0) - 0.01 * 6.5).abs() < 1e-6);
+        let grads = l2.backward()?;
+        for (g, &x) in grads.get(w.id()).unwrap().data().iter().zip(w.data().iter()) {
+            assert!((g - 0.02 * x).abs() < 1e-6);
+        }
+
+        // elastic net is the sum of both
+        let elastic = Penalty::ElasticNet { l1: 0.1, l2: 0.01 }.apply(&w)?;
+        assert!((elastic.get(0, 0) - (0.4 + 0.065)).abs() < 1e-6);
+        let grads = elastic.backward()?;
+        for (g, &x) in grads.get(w.id()).unwrap().data().iter().zip(w.data().iter()) {
+            assert!((g - (0.1 * sign(x) + 0.02 * x)).abs() < 1e-6);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn regularizers_select_parameters() -> Result<(), Box<dyn Error>> {
+
+        let mut nn = NN::new(vec![2, 3, 1], 0.1);
+        let value = |name: &str| nn.parameter(name).unwrap().value().powf(2.).sum_all().unwrap().get(0, 0);
+        let expected = 0.5 * (value("layers.0.weight") + value("layers.0.bias")) + 0.2 * value("layers.1.weight");
+
+        nn.set_regularizers(vec![
+            Regularizer::new(Penalty::L2(0.5)).for_layer("layers.0"),
+            Regularizer::new(Penalty::L2(0.2)).excluding_biases().for_layer("layers.1"),
+            // matches no parameter, layers.1 is not layers.10
+            Regularizer::new(Penalty::L1(1.)).for_layer("layers.10"),
+        ]);
+        let penalty = nn.penalty()?;
+        assert!((penalty.get(0, 0) - expected).abs() < 1e-5);
+
+        let grads = penalty.backward()?;
+        for param in nn.parameters() {
+            let grad = grads.get_param(param);
+            match param.name() {
+                "layers.1.bias" => assert!(grad.is_none()),
+                name => {
+                    let strength = if name.starts_with("layers.0") { 0.5 } else { 0.2 };
+                    let expected = param.value().mul_scalar(2. * strength);
+                    for (a, b) in grad.unwrap().data().iter().zip(expected.data().iter()) {
+                        assert!((a - b).abs() < 1e-6, "{}", name);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn penalty_is_reported_and_shrinks_weights() -> Result<(), Box<dyn Error>> {
+
+        let x = vec![vec![0., 0.], vec![0., 1.], vec![1., 0.], vec![1., 1.]];
+        let y = vec![0., 1., 1., 0.];
+
+        let mut plain = NN::new(vec![2, 4, 1], 0.5);
+        let mut regularized = plain.clone();
+        regularized.set_regularizers(vec![Regularizer::new(Penalty::L2(0.05)).excluding_biases()]);
+        let initial_penalty = regularized.penalty()?.get(0, 0);
+
+        let plain_history = plain.train_with_history(&x, &y, 4, 200)?;
+        let history = regularized.train_with_history(&x, &y, 4, 200)?;
+        assert_eq!(history.penalty[0], initial_penalty);
+
+        assert!(plain_history.penalty.iter().all(|&p| p == 0.));
+        assert_eq!(history.penalty.len(), 200);
+        assert!(history.penalty.iter().all(|&p| p > 0.));
+        // the first step starts from the same parameters, and the penalty is not part of the loss
+        assert!((history.loss[0] - plain_history.loss[0]).abs() < 1e-5);
+
+        let norm = |nn: &NN| nn.parameters()
+            .filter(|param| param.name().ends_with("weight"))
+            .map(|param| param.value().powf(2.).sum_all().unwrap().get(0, 0))
+            .sum::<f32>();
+        assert!(norm(&regularized) < norm(&plain));
+        Ok(())
+    }
+
+    #[test]
+    fn data_parallel_penalty_is_counted_once() -> Result<(), Box<dyn Error>> {
+
+        let x = (0..8).map(|i| vec![i as f32 / 8., 1. - i as f32 / 8.]).collect::<Vec<Vec<f32>>>();
+        let y = x.iter().map(|x| x[0] * x[1]).collect::<Vec<f32>>();
+
+        let mut single = NN::new(vec![2, 3, 1], 0.1);
+        single.set_regularizers(vec![Regularizer::new(Penalty::ElasticNet { l1: 0.01, l2: 0.01 })]);
+        let mut parallel = single.clone();
+        parallel.set_threads(4);
+
+        let a = single.train_with_history(&x, &y, 8, 10)?;
+        let b = parallel.train_with_history(&x, &y, 8, 10)?;
+        for (p, q) in a.penalty.iter().zip(b.penalty.iter()) {
+            assert!((p - q).abs() < 1e-4);
+        }
+        for ((name, a), (_, b)) in single.named_parameters().zip(parallel.named_parameters()) {
+            for (a, b) in a.data().iter().zip(b.data().iter()) {
+                assert!((a - b).abs() < 1e-4, "{}", name);
+            }
+        }
+        Ok(())
+    }
+}
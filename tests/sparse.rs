@@ -0,0 +1,62 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use std::error::Error;
+
+    use neural_network::*;
+
+    use crate::common::check_gradients;
+
+    fn one_hot() -> Result<SparseMatrix, MatrixError> {
+        // 3 samples, 5 features
+        SparseMatrix::from_triplets((3, 5), &[(0, 1, 1.), (1, 4, 1.), (2, 0, 1.), (2, 3, 2.)])
+    }
+
+    #[test]
+    fn sparse_construction_and_conversion() -> Result<(), Box<dyn Error>> {
+        let a = one_hot()?;
+        assert_eq!(a.nnz(), 4);
+        assert_eq!(a.to_dense().data(), &vec![
+            0., 1., 0., 0., 0.,
+            0., 0., 0., 0., 1.,
+            1., 0., 0., 2., 0.,
+        ]);
+
+        // duplicates are summed and entries are sorted
+        let b = SparseMatrix::from_triplets((2, 2), &[(1, 1, 1.), (0, 1, 2.), (1, 1, 3.)])?;
+        assert_eq!(b.triplets(), vec![(0, 1, 2.), (1, 1, 4.)]);
+
+        let dense: Matrix = (&a).into();
+        let c = SparseMatrix::from_dense(&dense);
+        assert_eq!(c.triplets(), a.triplets());
+        assert_eq!(a.t().to_dense().data(), dense.t().data());
+
+        assert!(matches!(
+            SparseMatrix::from_triplets((2, 2), &[(2, 0, 1.)]), 
+            Err(MatrixError::IndexOutOfBounds { index: 2, len: 2, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_dense_matmul() -> Result<(), Box<dyn Error>> {
+        let a = one_hot()?;
+        let w = Matrix::randn(-1., 1., (5, 2), true);
+
+        let expected = a.to_dense().matmul(&w)?;
+        let c = a.matmul(&w)?;
+        assert_eq!(c.shape(), (3, 2));
+        for (x, y) in c.data().iter().zip(expected.data().iter()) {
+            assert!((x - y).abs() < 1e-6);
+        }
+
+        assert!(a.matmul(&Matrix::zeros((4, 2), false)).is_err());
+
+        check_gradients(|xs| Ok(a.matmul(&xs[0])?.sigmoid()), std::slice::from_ref(&w))?;
+
+        Ok(())
+    }
+}
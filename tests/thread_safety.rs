@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+
+    use std::{error::Error, thread};
+
+    use neural_network::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn types_are_send_sync() {
+        assert_send_sync::<Matrix>();
+        assert_send_sync::<Matrix<f64>>();
+        assert_send_sync::<GradMap>();
+        assert_send_sync::<SparseMatrix>();
+        assert_send_sync::<Parameter>();
+        assert_send_sync::<Operator>();
+        assert_send_sync::<Dense>();
+        assert_send_sync::<Sequential>();
+        assert_send_sync::<NN>();
+    }
+
+    #[test]
+    fn shared_model_inference() -> Result<(), Box<dyn Error>> {
+
+        let x = vec![vec![0., 0.], vec![0., 1.], vec![1., 0.], vec![1., 1.]];
+        let y = vec![0., 1., 1., 0.];
+
+        let mut nn = NN::new(vec![2, 3, 1], 0.5);
+        nn.train(&x, &y, 4, 5)?;
+
+        let expected = nn.forward(Matrix::from(&x).t())?;
+
+        // every worker borrows the same model
+        let outputs = thread::scope(|s| {
+            let workers = (0..4)
+                .map(|_| s.spawn(|| nn
+                    .forward(Matrix::from(&x).t())
+                    .map(|out| out.data().clone())
+                    .map_err(|err| err.to_string())
+                ))
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap())
+                .collect::<Result<Vec<Vec<f32>>, String>>()
+        })?;
+
+        for out in outputs.iter() {
+            assert_eq!(out, expected.data());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn matrix_moved_across_threads() -> Result<(), Box<dyn Error>> {
+
+        let a = Matrix::<f32>::from_vec(vec![1., 2., 3.], (3, 1), true);
+        let b = a.mul_scalar(2.).sum_all()?;
+
+        // the graph is built on this thread and differentiated on another
+        let grads = thread::spawn(move || b.backward().map_err(|err| err.to_string()))
+            .join()
+            .unwrap()?;
+
+        assert_eq!(grads.get(a.id()).unwrap().data(), &vec![2., 2., 2.]);
+        Ok(())
+    }
+}